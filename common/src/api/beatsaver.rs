@@ -1,23 +1,285 @@
-use crate::types::map::{Map, Maps};
+use std::collections::HashMap;
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::{Map, Maps, Uploader};
+
+use super::{fetch_json, is_offline};
+
+/// The multi-hash endpoint rejects requests for more hashes than this.
+const MAX_HASHES_PER_REQUEST: usize = 50;
+
+/// A BeatSaver text search, optionally narrowed down by tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    pub order: SearchOrder,
+}
+
+/// Order BeatSaver returns search results in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SearchOrder {
+    #[default]
+    Relevance,
+    Latest,
+}
+
+impl SearchQuery {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// Empty tags are ignored.
+    pub fn include_tag(mut self, tag: &str) -> Self {
+        if !tag.is_empty() {
+            self.include_tags.push(tag.to_lowercase());
+        }
+        self
+    }
+
+    /// Empty tags are ignored.
+    pub fn exclude_tag(mut self, tag: &str) -> Self {
+        if !tag.is_empty() {
+            self.exclude_tags.push(tag.to_lowercase());
+        }
+        self
+    }
+
+    pub fn order(mut self, order: SearchOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Splits `tag:<tag>` and `-tag:<tag>` words out of a search box input,
+    /// everything else is searched for as text.
+    pub fn parse(input: &str) -> Self {
+        let mut query = SearchQuery::default();
+        let mut text = Vec::new();
+
+        for word in input.split_whitespace() {
+            if let Some(tag) = word.strip_prefix("-tag:") {
+                query = query.exclude_tag(tag);
+            } else if let Some(tag) = word.strip_prefix("tag:") {
+                query = query.include_tag(tag);
+            } else {
+                text.push(word);
+            }
+        }
+
+        query.text = text.join(" ");
+        query
+    }
+
+    pub(crate) fn url(&self, page_index: i32) -> String {
+        let mut params = vec![
+            ("q", self.text.clone()),
+            ("sortOrder", format!("{:?}", self.order)),
+        ];
+
+        // BeatSaver ANDs comma separated tags and excludes the ones prefixed with `!`
+        let tags: Vec<String> = self
+            .include_tags
+            .iter()
+            .cloned()
+            .chain(self.exclude_tags.iter().map(|tag| format!("!{}", tag)))
+            .collect();
+
+        if !tags.is_empty() {
+            params.push(("tags", tags.join(",")));
+        }
+
+        // the parameters are percent-encoded, so text like `Rock & Roll` or `C#` stays intact
+        Url::parse_with_params(
+            &format!("https://api.beatsaver.com/search/text/{}", page_index),
+            &params,
+        )
+        .expect("the search URL is valid")
+        .into()
+    }
+}
 
 pub async fn fetch_maps(
-    query: &str,
+    query: &SearchQuery,
     page_index: i32,
 ) -> Result<Vec<Map>, Box<dyn std::error::Error>> {
-    let resp: Maps = reqwest::get(format!(
-        "https://api.beatsaver.com/search/text/{}?q={}&sortOrder=Relevance",
-        page_index, query
-    ))
-    .await?
-    .json()
-    .await?;
+    let resp: Maps = fetch_json(&query.url(page_index)).await?;
+    Ok(resp.docs)
+}
+
+/// BeatSaver listings that can be browsed without a search query.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Feed {
+    Latest,
+    Curated,
+    TopRated,
+    MostPlayed,
+}
+
+impl Feed {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feed::Latest => "Latest",
+            Feed::Curated => "Curated",
+            Feed::TopRated => "Top Rated",
+            Feed::MostPlayed => "Most Played",
+        }
+    }
+
+    pub(crate) fn url(&self, page_index: i32) -> String {
+        match self {
+            Feed::Latest => format!(
+                "https://api.beatsaver.com/search/text/{}?sortOrder=Latest",
+                page_index
+            ),
+            Feed::Curated => format!(
+                "https://api.beatsaver.com/search/text/{}?sortOrder=Curated&curated=true",
+                page_index
+            ),
+            Feed::TopRated => format!(
+                "https://api.beatsaver.com/search/text/{}?sortOrder=Rating",
+                page_index
+            ),
+            Feed::MostPlayed => format!("https://api.beatsaver.com/maps/plays/{}", page_index),
+        }
+    }
+}
+
+pub async fn fetch_feed(
+    feed: Feed,
+    page_index: i32,
+) -> Result<Vec<Map>, Box<dyn std::error::Error>> {
+    let resp: Maps = fetch_json(&feed.url(page_index)).await?;
     Ok(resp.docs)
 }
 
 pub async fn fetch_map_details(id: &String) -> Result<Map, Box<dyn std::error::Error>> {
-    let resp: Map = reqwest::get(format!("https://api.beatsaver.com/maps/id/{}", id))
-        .await?
-        .json()
-        .await?;
+    let resp: Map = fetch_json(&format!("https://api.beatsaver.com/maps/id/{}", id)).await?;
     Ok(resp)
 }
+
+pub async fn fetch_maps_by_uploader(
+    uploader_id: i32,
+    page_index: i32,
+) -> Result<Vec<Map>, Box<dyn std::error::Error>> {
+    let resp: Maps = fetch_json(&uploader_maps_url(uploader_id, page_index)).await?;
+    Ok(resp.docs)
+}
+
+pub(crate) fn uploader_maps_url(uploader_id: i32, page_index: i32) -> String {
+    format!(
+        "https://api.beatsaver.com/maps/uploader/{}/{}",
+        uploader_id, page_index
+    )
+}
+
+/// Fetches an uploader including their aggregate stats.
+pub async fn fetch_uploader(uploader_id: i32) -> Result<Uploader, Box<dyn std::error::Error>> {
+    let resp: Uploader = fetch_json(&format!(
+        "https://api.beatsaver.com/users/id/{}",
+        uploader_id
+    ))
+    .await?;
+    Ok(resp)
+}
+
+pub async fn fetch_map_by_hash(hash: &str) -> Result<Map, Box<dyn std::error::Error>> {
+    let resp: Map = fetch_json(&format!("https://api.beatsaver.com/maps/hash/{}", hash)).await?;
+    Ok(resp)
+}
+
+/// Resolves many hashes at once through the multi-hash endpoint, returning a map of
+/// lowercase hash to `Map`. Hashes BeatSaver doesn't know are left out.
+pub async fn fetch_maps_by_hashes(
+    hashes: &[String],
+) -> Result<HashMap<String, Map>, Box<dyn std::error::Error>> {
+    let mut maps = HashMap::new();
+
+    for chunk in hashes.chunks(MAX_HASHES_PER_REQUEST) {
+        // with a single hash the endpoint returns the map itself instead of an object
+        if let [hash] = chunk {
+            match fetch_map_by_hash(hash).await {
+                Ok(map) => {
+                    maps.insert(hash.to_lowercase(), map);
+                }
+                Err(e) if is_offline() => return Err(e),
+                Err(_) => {}
+            }
+            continue;
+        }
+
+        let resp: HashMap<String, Option<Map>> = fetch_json(&format!(
+            "https://api.beatsaver.com/maps/hash/{}",
+            chunk.join(",")
+        ))
+        .await?;
+
+        maps.extend(
+            resp.into_iter()
+                .filter_map(|(hash, map)| Some((hash.to_lowercase(), map?))),
+        );
+    }
+
+    Ok(maps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(url: &str) -> HashMap<String, String> {
+        Url::parse(url)
+            .unwrap()
+            .query_pairs()
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_splits_out_tags() {
+        let query = SearchQuery::parse("tech tag:Anime  -tag:pop challenge");
+
+        assert_eq!(query.text, "tech challenge");
+        assert_eq!(query.include_tags, ["anime"]);
+        assert_eq!(query.exclude_tags, ["pop"]);
+        assert_eq!(query.order, SearchOrder::Relevance);
+    }
+
+    #[test]
+    fn parse_ignores_empty_tags() {
+        let query = SearchQuery::parse("tag: -tag: song");
+
+        assert_eq!(query, SearchQuery::new("song"));
+    }
+
+    #[test]
+    fn url_encodes_the_text() {
+        let query = SearchQuery::new("Rock & Roll #1 C#");
+        let url = query.url(2);
+
+        assert!(url.starts_with("https://api.beatsaver.com/search/text/2?"));
+        assert!(!url.contains(' '));
+
+        let params = params(&url);
+        assert_eq!(params["q"], "Rock & Roll #1 C#");
+        assert_eq!(params["sortOrder"], "Relevance");
+        assert!(!params.contains_key("tags"));
+    }
+
+    #[test]
+    fn url_joins_included_and_excluded_tags() {
+        let query =
+            SearchQuery::parse("a&b tag:anime tag:tech -tag:pop").order(SearchOrder::Latest);
+        let params = params(&query.url(0));
+
+        assert_eq!(params["q"], "a&b");
+        assert_eq!(params["sortOrder"], "Latest");
+        assert_eq!(params["tags"], "anime,tech,!pop");
+    }
+}
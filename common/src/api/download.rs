@@ -0,0 +1,89 @@
+use std::{
+    error::Error,
+    fs, io,
+    path::PathBuf,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use log::Level;
+
+use crate::{
+    store::{downloads_dir, library::Library},
+    types::map::Map,
+};
+
+use super::{client, log_request, OFFLINE};
+
+/// Zips take longer than the other responses, this is for the whole download.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Downloads the zip of the map's published version into the downloads dir and adds the map
+/// to the library, returning its path. The file is named like the folders Beat Saber keeps
+/// custom levels in.
+pub async fn download_map(map: &Map) -> Result<PathBuf, Box<dyn Error>> {
+    let version = map
+        .published_version()
+        .ok_or_else(|| io::Error::other("Map has no version to download"))?;
+
+    // zips are big and rarely downloaded twice, so they skip the response cache
+    let start = Instant::now();
+    let url = &version.download_url;
+    let response = match client().get(url).timeout(DOWNLOAD_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            let status = if e.is_connect() || e.is_timeout() || e.is_request() {
+                OFFLINE.store(true, Ordering::Relaxed);
+                "offline"
+            } else {
+                "failed"
+            };
+            log_request(Level::Error, url, status, start, "skip", Some(&e));
+            return Err(Box::new(e));
+        }
+    };
+
+    let status = response.status();
+    let bytes = match response.error_for_status() {
+        Ok(response) => response.bytes().await,
+        Err(e) => Err(e),
+    };
+    match bytes {
+        Ok(_) => log_request(Level::Info, url, status.as_str(), start, "skip", None),
+        Err(ref e) => {
+            if e.is_timeout() {
+                OFFLINE.store(true, Ordering::Relaxed);
+            }
+            log_request(Level::Error, url, status.as_str(), start, "skip", Some(e));
+        }
+    }
+    let bytes = bytes?;
+
+    let dir = downloads_dir();
+    fs::create_dir_all(&dir)?;
+
+    let name = format!(
+        "{} ({} - {}).zip",
+        map.id, map.metadata.song_name, map.metadata.level_author_name
+    );
+    let path = dir.join(sanitize_file_name(&name));
+    fs::write(&path, bytes)?;
+
+    // the zip is there either way, it's only missing from offline searches
+    let _ = Library::load().and_then(|mut library| {
+        library.add(map);
+        library.save()
+    });
+    Ok(path)
+}
+
+/// Replaces characters that aren't allowed in file names on common file systems.
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
@@ -1,2 +1,172 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use log::{log, Level};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+
+use crate::store::cache;
+
 pub mod beatsaver;
+pub mod download;
+pub mod paginator;
 pub mod scoresaber;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Whether the last request couldn't reach the network, meaning data is served from the cache.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// The client every request goes through, with timeouts so a server that accepts the
+/// connection but never answers counts as unreachable instead of hanging the app.
+pub(crate) fn client() -> &'static Client {
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("the HTTP client can be built")
+    })
+}
+
+/// Checks whether BeatSaver can be reached again, updating [`is_offline`] either way.
+pub async fn check_connection() -> bool {
+    let reachable = match client()
+        .head("https://api.beatsaver.com/")
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => !response.status().is_server_error(),
+        Err(_) => false,
+    };
+    OFFLINE.store(!reachable, Ordering::Relaxed);
+    reachable
+}
+
+/// Fetches `url`, caching successful responses and falling back to the cache when the
+/// network is unreachable or the server fails. Other unsuccessful statuses are errors.
+pub async fn fetch_bytes(url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let start = Instant::now();
+
+    match client().get(url).send().await {
+        Ok(response) => {
+            let status = response.status();
+
+            if let Err(e) = response.error_for_status_ref() {
+                // a server that's down is no better than an unreachable one
+                if status.is_server_error() {
+                    OFFLINE.store(true, Ordering::Relaxed);
+                    return from_cache(url, status.as_str(), start, e);
+                }
+
+                OFFLINE.store(false, Ordering::Relaxed);
+                log_request(Level::Warn, url, status.as_str(), start, "skip", Some(&e));
+                return Err(Box::new(e));
+            }
+
+            let bytes = match response.bytes().await {
+                Ok(bytes) => bytes.to_vec(),
+                // the server stopped answering halfway through
+                Err(e) if e.is_timeout() => {
+                    OFFLINE.store(true, Ordering::Relaxed);
+                    return from_cache(url, "offline", start, e);
+                }
+                Err(e) => {
+                    log_request(Level::Error, url, status.as_str(), start, "skip", Some(&e));
+                    return Err(Box::new(e));
+                }
+            };
+
+            OFFLINE.store(false, Ordering::Relaxed);
+            // a failed cache write only costs us offline support for this response
+            let cache = match cache::write(url, &bytes) {
+                Ok(()) => "write",
+                Err(_) => "skip",
+            };
+            log_request(Level::Info, url, status.as_str(), start, cache, None);
+            Ok(bytes)
+        }
+        Err(e) if e.is_connect() || e.is_timeout() || e.is_request() => {
+            OFFLINE.store(true, Ordering::Relaxed);
+            from_cache(url, "offline", start, e)
+        }
+        Err(e) => {
+            log_request(Level::Error, url, "failed", start, "skip", Some(&e));
+            Err(Box::new(e))
+        }
+    }
+}
+
+/// The cached response for `url` in place of a request that failed with `error`.
+fn from_cache(
+    url: &str,
+    status: &str,
+    start: Instant,
+    error: reqwest::Error,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    match cache::read(url) {
+        Some(bytes) => {
+            log_request(Level::Warn, url, status, start, "hit", Some(&error));
+            Ok(bytes)
+        }
+        None => {
+            log_request(Level::Error, url, status, start, "miss", Some(&error));
+            Err(Box::new(error))
+        }
+    }
+}
+
+/// Logs a finished request under the `http` target as `key=value` pairs, so the log can be
+/// searched by any of them.
+pub(crate) fn log_request(
+    level: Level,
+    url: &str,
+    status: &str,
+    start: Instant,
+    cache: &str,
+    error: Option<&dyn Display>,
+) {
+    let latency = start.elapsed().as_millis();
+
+    match error {
+        Some(error) => log!(
+            target: "http",
+            level,
+            "GET url={} status={} latency_ms={} cache={} error=\"{}\"",
+            url,
+            status,
+            latency,
+            cache,
+            error
+        ),
+        None => log!(
+            target: "http",
+            level,
+            "GET url={} status={} latency_ms={} cache={}",
+            url,
+            status,
+            latency,
+            cache
+        ),
+    }
+}
+
+pub(crate) async fn fetch_json<T: DeserializeOwned>(url: &str) -> Result<T, Box<dyn Error>> {
+    let bytes = fetch_bytes(url).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
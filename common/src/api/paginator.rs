@@ -0,0 +1,169 @@
+use std::error::Error;
+
+use crate::types::map::{Map, Maps, SearchInfo};
+
+use super::{
+    beatsaver::{uploader_maps_url, Feed, SearchQuery},
+    fetch_json,
+};
+
+/// BeatSaver returns this many maps per page, a shorter page is the last one.
+const PAGE_SIZE: usize = 20;
+
+/// A paginated BeatSaver listing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageSource {
+    Search(SearchQuery),
+    Feed(Feed),
+    Uploader(i32),
+}
+
+impl PageSource {
+    fn url(&self, page_index: i32) -> String {
+        match self {
+            PageSource::Search(query) => query.url(page_index),
+            PageSource::Feed(feed) => feed.url(page_index),
+            PageSource::Uploader(uploader_id) => uploader_maps_url(*uploader_id, page_index),
+        }
+    }
+}
+
+/// Fetches the pages of a listing one after another and keeps track of whether there are more.
+#[derive(Debug, Clone)]
+pub struct Paginator {
+    source: PageSource,
+    next_page: i32,
+    total: Option<i32>,
+    has_more: bool,
+}
+
+impl Paginator {
+    pub fn new(source: PageSource) -> Paginator {
+        Paginator {
+            source,
+            next_page: 0,
+            total: None,
+            has_more: true,
+        }
+    }
+
+    /// Fetches the next page, returning no maps once the listing is exhausted.
+    pub async fn fetch_next(&mut self) -> Result<Vec<Map>, Box<dyn Error>> {
+        if !self.has_more {
+            return Ok(Vec::new());
+        }
+
+        let resp: Maps = fetch_json(&self.source.url(self.next_page)).await?;
+        self.record_page(resp.docs.len(), resp.info);
+
+        Ok(resp.docs)
+    }
+
+    /// Moves past a fetched page of `maps` maps, working out whether another one follows.
+    fn record_page(&mut self, maps: usize, info: Option<SearchInfo>) {
+        self.next_page += 1;
+
+        // only searches tell how many pages there are, other listings end on a short page
+        self.has_more = match info {
+            Some(info) => {
+                self.total = Some(info.total);
+                self.next_page < info.pages
+            }
+            None => maps >= PAGE_SIZE,
+        };
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.has_more
+    }
+
+    /// Number of pages fetched so far.
+    pub fn pages_loaded(&self) -> i32 {
+        self.next_page
+    }
+
+    /// Total number of results, if the API reports it for this listing.
+    pub fn total(&self) -> Option<i32> {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stub() -> Paginator {
+        Paginator::new(PageSource::Uploader(0))
+    }
+
+    #[test]
+    fn short_page_is_the_last_one() {
+        let mut paginator = stub();
+        assert!(paginator.has_more());
+
+        paginator.record_page(PAGE_SIZE, None);
+        assert!(paginator.has_more());
+
+        paginator.record_page(PAGE_SIZE - 1, None);
+        assert!(!paginator.has_more());
+        assert_eq!(paginator.pages_loaded(), 2);
+        assert_eq!(paginator.total(), None);
+    }
+
+    #[test]
+    fn full_last_page_needs_one_more_fetch() {
+        let mut paginator = stub();
+
+        // without info a full page can't be told apart from the last one
+        paginator.record_page(PAGE_SIZE, None);
+        assert!(paginator.has_more());
+
+        paginator.record_page(0, None);
+        assert!(!paginator.has_more());
+    }
+
+    #[test]
+    fn empty_first_page_ends_the_listing() {
+        let mut paginator = stub();
+
+        paginator.record_page(0, None);
+        assert!(!paginator.has_more());
+        assert_eq!(paginator.pages_loaded(), 1);
+    }
+
+    #[test]
+    fn info_decides_when_present() {
+        let mut paginator = stub();
+        let info = SearchInfo {
+            total: 2 * PAGE_SIZE as i32,
+            pages: 2,
+        };
+
+        paginator.record_page(PAGE_SIZE, Some(info));
+        assert!(paginator.has_more());
+        assert_eq!(paginator.total(), Some(40));
+
+        // an exact multiple of the page size still ends on the last page
+        paginator.record_page(PAGE_SIZE, Some(info));
+        assert!(!paginator.has_more());
+    }
+
+    #[test]
+    fn info_wins_over_the_page_length() {
+        let mut empty = stub();
+        empty.record_page(0, Some(SearchInfo { total: 0, pages: 0 }));
+        assert!(!empty.has_more());
+        assert_eq!(empty.total(), Some(0));
+
+        // a short page isn't the last one if the search says there are more
+        let mut short = stub();
+        short.record_page(
+            5,
+            Some(SearchInfo {
+                total: 45,
+                pages: 3,
+            }),
+        );
+        assert!(short.has_more());
+    }
+}
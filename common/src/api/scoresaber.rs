@@ -1,15 +1,15 @@
 use crate::types::ss_leaderboard::{LeaderBoardInfo, Leaderboard, Score};
 
+use super::fetch_json;
+
 pub async fn fetch_leaderboard(
     leaderboard_id: u32,
     page: u32,
 ) -> Result<Vec<Score>, Box<dyn std::error::Error>> {
-    let resp: Leaderboard = reqwest::get(&format!(
+    let resp: Leaderboard = fetch_json(&format!(
         "https://scoresaber.com/api/leaderboard/by-id/{}/scores?page={}",
         leaderboard_id, page
     ))
-    .await?
-    .json()
     .await?;
 
     Ok(resp.scores)
@@ -20,12 +20,10 @@ pub async fn fetch_leaderboard_info(
     difficulty: u8,
     game_mode: &str,
 ) -> Result<LeaderBoardInfo, Box<dyn std::error::Error>> {
-    let resp: LeaderBoardInfo = reqwest::get(&format!(
+    let resp: LeaderBoardInfo = fetch_json(&format!(
         "https://scoresaber.com/api/leaderboard/by-hash/{}/info?difficulty={}&gameMode={}",
         song_hash, difficulty, game_mode
     ))
-    .await?
-    .json()
     .await?;
 
     Ok(resp)
@@ -0,0 +1,540 @@
+use std::{error::Error, fmt};
+
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+use crate::types::map::Map;
+
+/// A filter over already loaded maps, parsed from expressions like
+/// `mapper:joetastic bpm>180 nps:5..8 ranked diff:ExpertPlus -tag:anime "exact phrase"`.
+///
+/// Every term has to match. Bare words are fuzzy matched against the id, song name, artist
+/// and mapper, quoted phrases have to appear as is in those or the publish date. `field:value`
+/// terms are matched against one field and numeric fields also take `>`, `>=`, `<`, `<=`, `=`
+/// and `min..max` ranges. Any term can be negated with a leading `-`. Difficulty terms match
+/// if any difficulty of the map does.
+#[derive(Default)]
+pub struct Filter {
+    terms: Vec<Term>,
+    fuzzy_words: Vec<String>,
+    matcher: SkimMatcherV2,
+}
+
+/// How well a map matched the bare words of a filter and which characters they matched.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Char indices of the matched characters in the id, song name, artist and mapper.
+    pub id: Vec<usize>,
+    pub song_name: Vec<usize>,
+    pub song_author: Vec<usize>,
+    pub level_author: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    condition: Condition,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    /// A bare word, fuzzy matched unless negated.
+    Word(String),
+    Text(String),
+    Field(TextField, String),
+    Number(NumberField, Comparison),
+    Difficulty(String),
+    Characteristic(String),
+    Tag(String),
+    Flag(Flag),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TextField {
+    Id,
+    Song,
+    Artist,
+    Mapper,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NumberField {
+    Bpm,
+    Duration,
+    Rating,
+    Upvotes,
+    Downvotes,
+    Plays,
+    Downloads,
+    Nps,
+    Njs,
+    Stars,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparison {
+    Equal(f32),
+    Less(f32),
+    LessOrEqual(f32),
+    Greater(f32),
+    GreaterOrEqual(f32),
+    Range(Option<f32>, Option<f32>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Flag {
+    Ranked,
+    Qualified,
+    Curated,
+}
+
+/// Why a filter expression couldn't be parsed, meant to be shown to the user as is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError(String);
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for FilterError {}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Filter, FilterError> {
+        let mut filter = Filter::default();
+
+        for token in tokenize(input)? {
+            match parse_term(&token)? {
+                Term {
+                    negated: false,
+                    condition: Condition::Word(word),
+                } => filter.fuzzy_words.push(word),
+                term => filter.terms.push(term),
+            }
+        }
+
+        filter.matcher = SkimMatcherV2::default().ignore_case();
+        Ok(filter)
+    }
+
+    /// Whether the filter has bare words, i.e. its matches have a meaningful score.
+    pub fn is_fuzzy(&self) -> bool {
+        !self.fuzzy_words.is_empty()
+    }
+
+    pub fn matches(&self, map: &Map) -> bool {
+        self.fuzzy_match(map).is_some()
+    }
+
+    /// Matches the map against the filter, scoring it by how well the bare words matched.
+    pub fn fuzzy_match(&self, map: &Map) -> Option<FuzzyMatch> {
+        if !self
+            .terms
+            .iter()
+            .all(|term| term.condition.matches(map) != term.negated)
+        {
+            return None;
+        }
+
+        let fields = [
+            &map.id,
+            &map.metadata.song_name,
+            &map.metadata.song_author_name,
+            &map.metadata.level_author_name,
+        ];
+        let line = fields.map(String::as_str).join(" ");
+
+        let mut score = 0;
+        let mut indices = Vec::new();
+        for word in &self.fuzzy_words {
+            let (word_score, mut word_indices) = self.matcher.fuzzy_indices(&line, word)?;
+            score += word_score;
+            indices.append(&mut word_indices);
+        }
+
+        // the indices are into the joined line, shift them back into the field they fall in
+        let mut field_indices: [Vec<usize>; 4] = Default::default();
+        let mut start = 0;
+        for (field, field_indices) in fields.iter().zip(field_indices.iter_mut()) {
+            let end = start + field.chars().count();
+            *field_indices = indices
+                .iter()
+                .filter(|i| (start..end).contains(*i))
+                .map(|i| i - start)
+                .collect();
+            start = end + 1;
+        }
+        let [id, song_name, song_author, level_author] = field_indices;
+
+        Some(FuzzyMatch {
+            score,
+            id,
+            song_name,
+            song_author,
+            level_author,
+        })
+    }
+}
+
+/// Splits the input on whitespace outside of double quotes. Quotes are kept so phrases can
+/// be told apart from bare words.
+fn tokenize(input: &str) -> Result<Vec<String>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                token.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(FilterError("unterminated quote".to_owned()));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, FilterError> {
+    let (negated, token) = match token.strip_prefix('-') {
+        Some("") => return Err(FilterError("expected a term after `-`".to_owned())),
+        Some(rest) => (true, rest),
+        None => (false, token),
+    };
+
+    Ok(Term {
+        negated,
+        condition: parse_condition(token)?,
+    })
+}
+
+fn parse_condition(token: &str) -> Result<Condition, FilterError> {
+    if token.starts_with('"') {
+        return Ok(Condition::Text(unquote(token).to_lowercase()));
+    }
+
+    let operator_start = token.find([':', '<', '>', '=']);
+    let field = operator_start.map(|i| &token[..i]);
+
+    let (field, rest) = match (field, operator_start) {
+        (Some(field), Some(i)) if !field.is_empty() && field.chars().all(char::is_alphabetic) => {
+            (field.to_lowercase(), &token[i..])
+        }
+        _ => {
+            return Ok(match token.to_lowercase().as_str() {
+                "ranked" => Condition::Flag(Flag::Ranked),
+                "qualified" => Condition::Flag(Flag::Qualified),
+                "curated" => Condition::Flag(Flag::Curated),
+                word => Condition::Word(word.to_owned()),
+            })
+        }
+    };
+
+    let (operator, value) = split_operator(rest);
+    let value = unquote(value);
+
+    if value.is_empty() {
+        return Err(FilterError(format!("missing value for `{}`", field)));
+    }
+
+    if let Some(number_field) = number_field(&field) {
+        let comparison = parse_comparison(operator, value)
+            .ok_or_else(|| FilterError(format!("`{}` needs a number, got `{}`", field, value)))?;
+        return Ok(Condition::Number(number_field, comparison));
+    }
+
+    if operator != ":" && operator != "=" {
+        return Err(FilterError(format!(
+            "`{}` can't be compared with `{}`",
+            field, operator
+        )));
+    }
+
+    let value = value.to_lowercase();
+    Ok(match field.as_str() {
+        "id" | "key" => Condition::Field(TextField::Id, value),
+        "song" | "name" | "title" => Condition::Field(TextField::Song, value),
+        "artist" | "author" => Condition::Field(TextField::Artist, value),
+        "mapper" => Condition::Field(TextField::Mapper, value),
+        "diff" | "difficulty" => Condition::Difficulty(value),
+        "mode" | "characteristic" => Condition::Characteristic(value),
+        "tag" => Condition::Tag(value),
+        _ => return Err(FilterError(format!("unknown field `{}`", field))),
+    })
+}
+
+fn split_operator(rest: &str) -> (&str, &str) {
+    for operator in [">=", "<=", ":", "<", ">", "="] {
+        if let Some(value) = rest.strip_prefix(operator) {
+            return (operator, value);
+        }
+    }
+    ("", rest)
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches('"')
+}
+
+fn number_field(field: &str) -> Option<NumberField> {
+    Some(match field {
+        "bpm" => NumberField::Bpm,
+        "duration" | "length" => NumberField::Duration,
+        "rating" | "score" => NumberField::Rating,
+        "upvotes" | "up" => NumberField::Upvotes,
+        "downvotes" | "down" => NumberField::Downvotes,
+        "plays" => NumberField::Plays,
+        "downloads" => NumberField::Downloads,
+        "nps" => NumberField::Nps,
+        "njs" => NumberField::Njs,
+        "stars" => NumberField::Stars,
+        _ => return None,
+    })
+}
+
+fn parse_comparison(operator: &str, value: &str) -> Option<Comparison> {
+    let number = |value: &str| value.parse::<f32>().ok();
+
+    Some(match operator {
+        ">" => Comparison::Greater(number(value)?),
+        ">=" => Comparison::GreaterOrEqual(number(value)?),
+        "<" => Comparison::Less(number(value)?),
+        "<=" => Comparison::LessOrEqual(number(value)?),
+        _ => match value.split_once("..") {
+            Some((min, max)) => {
+                let bound = |value: &str| match value {
+                    "" => Some(None),
+                    value => number(value).map(Some),
+                };
+                Comparison::Range(bound(min)?, bound(max)?)
+            }
+            None => Comparison::Equal(number(value)?),
+        },
+    })
+}
+
+impl Comparison {
+    fn matches(&self, value: f32) -> bool {
+        match *self {
+            Comparison::Equal(n) => (value - n).abs() < 0.01,
+            Comparison::Less(n) => value < n,
+            Comparison::LessOrEqual(n) => value <= n,
+            Comparison::Greater(n) => value > n,
+            Comparison::GreaterOrEqual(n) => value >= n,
+            Comparison::Range(min, max) => {
+                !min.is_some_and(|min| value < min) && !max.is_some_and(|max| value > max)
+            }
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, map: &Map) -> bool {
+        let diffs = map
+            .published_version()
+            .map(|version| version.diffs.as_slice())
+            .unwrap_or_default();
+
+        match self {
+            Condition::Word(text) | Condition::Text(text) => format!(
+                "{} {} {} {} {}",
+                map.id,
+                map.metadata.song_name,
+                map.metadata.song_author_name,
+                map.metadata.level_author_name,
+                map.last_published_at.format("%Y-%m-%d")
+            )
+            .to_lowercase()
+            .contains(text),
+            Condition::Field(field, text) => {
+                let value = match field {
+                    TextField::Id => &map.id,
+                    TextField::Song => &map.metadata.song_name,
+                    TextField::Artist => &map.metadata.song_author_name,
+                    TextField::Mapper => &map.metadata.level_author_name,
+                };
+                value.to_lowercase().contains(text)
+            }
+            Condition::Number(field, comparison) => {
+                let value = match field {
+                    NumberField::Bpm => map.metadata.bpm,
+                    NumberField::Duration => map.metadata.duration as f32,
+                    NumberField::Rating => map.stats.score * 100.0,
+                    NumberField::Upvotes => map.stats.upvotes as f32,
+                    NumberField::Downvotes => map.stats.downvotes as f32,
+                    NumberField::Plays => map.stats.plays as f32,
+                    NumberField::Downloads => map.stats.downloads as f32,
+                    NumberField::Nps => {
+                        return diffs.iter().any(|diff| comparison.matches(diff.nps))
+                    }
+                    NumberField::Njs => {
+                        return diffs.iter().any(|diff| comparison.matches(diff.njs))
+                    }
+                    NumberField::Stars => {
+                        return diffs
+                            .iter()
+                            .filter_map(|diff| diff.stars)
+                            .any(|stars| comparison.matches(stars))
+                    }
+                };
+                comparison.matches(value)
+            }
+            Condition::Difficulty(difficulty) => diffs
+                .iter()
+                .any(|diff| diff.difficulty.eq_ignore_ascii_case(difficulty)),
+            Condition::Characteristic(characteristic) => diffs
+                .iter()
+                .any(|diff| diff.characteristic.eq_ignore_ascii_case(characteristic)),
+            Condition::Tag(tag) => map.has_tag(tag),
+            Condition::Flag(Flag::Ranked) => map.ranked,
+            Condition::Flag(Flag::Qualified) => map.qualified,
+            Condition::Flag(Flag::Curated) => map.curated_at.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: &str) -> String {
+        match Filter::parse(input) {
+            Ok(_) => panic!("`{}` should not parse", input),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_phrases_together() {
+        assert_eq!(
+            tokenize(r#"  bpm>180 "exact  phrase" -tag:anime "#).unwrap(),
+            ["bpm>180", "\"exact  phrase\"", "-tag:anime"]
+        );
+        assert_eq!(
+            tokenize(r#"song:"two words""#).unwrap(),
+            ["song:\"two words\""]
+        );
+        assert!(tokenize("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parses_the_example_expression() {
+        let filter = Filter::parse(
+            r#"mapper:joetastic bpm>180 nps:5..8 ranked diff:ExpertPlus -tag:anime "exact phrase""#,
+        )
+        .unwrap();
+
+        assert!(!filter.is_fuzzy());
+        assert_eq!(filter.terms.len(), 7);
+        assert!(matches!(
+            &filter.terms[0].condition,
+            Condition::Field(TextField::Mapper, mapper) if mapper == "joetastic"
+        ));
+        assert!(matches!(
+            filter.terms[1].condition,
+            Condition::Number(NumberField::Bpm, Comparison::Greater(bpm)) if bpm == 180.0
+        ));
+        assert!(matches!(
+            filter.terms[2].condition,
+            Condition::Number(NumberField::Nps, Comparison::Range(Some(min), Some(max)))
+                if min == 5.0 && max == 8.0
+        ));
+        assert!(matches!(
+            filter.terms[3].condition,
+            Condition::Flag(Flag::Ranked)
+        ));
+        assert!(matches!(
+            &filter.terms[4].condition,
+            Condition::Difficulty(diff) if diff == "expertplus"
+        ));
+        assert!(filter.terms[5].negated);
+        assert!(matches!(&filter.terms[5].condition, Condition::Tag(tag) if tag == "anime"));
+        assert!(matches!(
+            &filter.terms[6].condition,
+            Condition::Text(text) if text == "exact phrase"
+        ));
+        assert_eq!(filter.terms.iter().filter(|term| term.negated).count(), 1);
+    }
+
+    #[test]
+    fn bare_words_are_fuzzy_unless_negated() {
+        let filter = Filter::parse("Camellia -remix").unwrap();
+
+        assert!(filter.is_fuzzy());
+        assert_eq!(filter.fuzzy_words, ["camellia"]);
+        assert_eq!(filter.terms.len(), 1);
+        assert!(filter.terms[0].negated);
+        assert!(matches!(&filter.terms[0].condition, Condition::Word(word) if word == "remix"));
+    }
+
+    #[test]
+    fn parses_comparisons() {
+        let comparison = |token: &str| match parse_condition(token).unwrap() {
+            Condition::Number(_, comparison) => comparison,
+            condition => panic!("`{}` parsed to {:?}", token, condition),
+        };
+
+        assert!(matches!(comparison("stars>=7.5"), Comparison::GreaterOrEqual(n) if n == 7.5));
+        assert!(matches!(comparison("njs<=16"), Comparison::LessOrEqual(n) if n == 16.0));
+        assert!(matches!(comparison("plays<100"), Comparison::Less(n) if n == 100.0));
+        assert!(matches!(comparison("upvotes=10"), Comparison::Equal(n) if n == 10.0));
+        assert!(matches!(comparison("BPM:200"), Comparison::Equal(n) if n == 200.0));
+        assert!(matches!(
+            comparison("nps:5.."),
+            Comparison::Range(Some(min), None) if min == 5.0
+        ));
+        assert!(matches!(
+            comparison("duration:..120"),
+            Comparison::Range(None, Some(max)) if max == 120.0
+        ));
+    }
+
+    #[test]
+    fn ranges_include_their_bounds() {
+        let range = Comparison::Range(Some(5.0), Some(8.0));
+        assert!(range.matches(5.0));
+        assert!(range.matches(6.5));
+        assert!(range.matches(8.0));
+        assert!(!range.matches(4.9));
+        assert!(!range.matches(8.1));
+
+        let open = Comparison::Range(None, Some(8.0));
+        assert!(open.matches(-100.0));
+        assert!(!open.matches(9.0));
+    }
+
+    #[test]
+    fn tokens_with_operators_but_no_field_are_words() {
+        assert!(
+            matches!(parse_condition("3:16").unwrap(), Condition::Word(word) if word == "3:16")
+        );
+        assert!(matches!(
+            parse_condition(">_<").unwrap(),
+            Condition::Word(_)
+        ));
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(error(r#"song:"never closed"#), "unterminated quote");
+        assert_eq!(error("ranked -"), "expected a term after `-`");
+        assert_eq!(error("mapper:"), "missing value for `mapper`");
+        assert_eq!(error("bpm>fast"), "`bpm` needs a number, got `fast`");
+        assert_eq!(error("nps:5..x"), "`nps` needs a number, got `5..x`");
+        assert_eq!(error("tag>anime"), "`tag` can't be compared with `>`");
+        assert_eq!(error("colour:red"), "unknown field `colour`");
+    }
+}
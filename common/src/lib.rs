@@ -1,2 +1,5 @@
 pub mod api;
+pub mod filter;
+pub mod sort;
+pub mod store;
 pub mod types;
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// What maps can be sorted by.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    Id,
+    SongName,
+    SongAuthor,
+    LevelAuthor,
+    Date,
+    Upvotes,
+    Downvotes,
+    Rating,
+    Plays,
+    Downloads,
+    Bpm,
+    Duration,
+    MaxNps,
+    Stars,
+    Ranked,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 15] = [
+        SortKey::Id,
+        SortKey::SongName,
+        SortKey::SongAuthor,
+        SortKey::LevelAuthor,
+        SortKey::Date,
+        SortKey::Upvotes,
+        SortKey::Downvotes,
+        SortKey::Rating,
+        SortKey::Plays,
+        SortKey::Downloads,
+        SortKey::Bpm,
+        SortKey::Duration,
+        SortKey::MaxNps,
+        SortKey::Stars,
+        SortKey::Ranked,
+    ];
+
+    pub fn compare(&self, a: &Map, b: &Map) -> Ordering {
+        match self {
+            SortKey::Id => a.id.cmp(&b.id),
+            SortKey::SongName => a
+                .metadata
+                .song_name
+                .to_ascii_lowercase()
+                .cmp(&b.metadata.song_name.to_ascii_lowercase()),
+            SortKey::SongAuthor => a
+                .metadata
+                .song_author_name
+                .to_ascii_lowercase()
+                .cmp(&b.metadata.song_author_name.to_ascii_lowercase()),
+            SortKey::LevelAuthor => a
+                .metadata
+                .level_author_name
+                .to_ascii_lowercase()
+                .cmp(&b.metadata.level_author_name.to_ascii_lowercase()),
+            SortKey::Date => a.last_published_at.cmp(&b.last_published_at),
+            SortKey::Upvotes => a.stats.upvotes.cmp(&b.stats.upvotes),
+            SortKey::Downvotes => a.stats.downvotes.cmp(&b.stats.downvotes),
+            SortKey::Rating => a.stats.score.total_cmp(&b.stats.score),
+            SortKey::Plays => a.stats.plays.cmp(&b.stats.plays),
+            SortKey::Downloads => a.stats.downloads.cmp(&b.stats.downloads),
+            SortKey::Bpm => a.metadata.bpm.total_cmp(&b.metadata.bpm),
+            SortKey::Duration => a.metadata.duration.cmp(&b.metadata.duration),
+            SortKey::MaxNps => a.max_nps().total_cmp(&b.max_nps()),
+            // unranked maps sort below every ranked one
+            SortKey::Stars => a
+                .max_stars()
+                .unwrap_or(-1.0)
+                .total_cmp(&b.max_stars().unwrap_or(-1.0)),
+            SortKey::Ranked => (a.ranked, a.qualified).cmp(&(b.ranked, b.qualified)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Sort {
+    pub key: SortKey,
+    pub direction: SortDirection,
+}
+
+impl Sort {
+    pub fn compare(&self, a: &Map, b: &Map) -> Ordering {
+        match self.direction {
+            SortDirection::Ascending => self.key.compare(a, b),
+            SortDirection::Descending => self.key.compare(b, a),
+        }
+    }
+}
+
+/// Orders the maps by the first sort, ties are broken by the following ones.
+pub fn sort_maps(maps: &mut [Map], sort: &[Sort]) {
+    if sort.is_empty() {
+        return;
+    }
+
+    maps.sort_by(|a, b| {
+        sort.iter()
+            .map(|s| s.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
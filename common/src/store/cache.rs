@@ -0,0 +1,70 @@
+use std::{
+    cmp::Reverse,
+    error::Error,
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use sha1::{Digest, Sha1};
+
+use super::{cache_dir, write_atomic};
+
+/// Entries older than this are removed by [`prune`].
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Most bytes kept in the cache, [`prune`] removes the oldest entries above it.
+const MAX_SIZE: u64 = 500 * 1024 * 1024;
+
+// a stable digest, so entries still match after the program is rebuilt
+fn path_for(url: &str) -> PathBuf {
+    cache_dir().join(format!("{:x}", Sha1::digest(url.as_bytes())))
+}
+
+/// Returns the last successful response for `url`, if there is one.
+pub fn read(url: &str) -> Option<Vec<u8>> {
+    fs::read(path_for(url)).ok()
+}
+
+/// Stores a response atomically, since a truncated entry would be served as valid offline.
+pub fn write(url: &str, body: &[u8]) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(cache_dir())?;
+    write_atomic(&path_for(url), body)
+}
+
+/// Removes the entries older than `MAX_AGE`, then the oldest ones until the cache fits in
+/// `MAX_SIZE`. Returns how many entries were removed.
+pub fn prune() -> Result<usize, Box<dyn Error>> {
+    let dir = match fs::read_dir(cache_dir()) {
+        Ok(dir) => dir,
+        // nothing cached yet
+        Err(_) => return Ok(0),
+    };
+
+    let mut entries = Vec::new();
+    for entry in dir {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((modified, metadata.len(), entry.path()));
+        }
+    }
+    // newest first, so whatever is left over the size is the oldest
+    entries.sort_by_key(|(modified, _, _)| Reverse(*modified));
+
+    let now = SystemTime::now();
+    let mut size = 0;
+    let mut removed = 0;
+
+    for (modified, len, path) in entries {
+        let age = now.duration_since(modified).unwrap_or_default();
+        size += len;
+
+        if age > MAX_AGE || size > MAX_SIZE {
+            fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,36 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+use super::{read_json, write_json};
+
+const QUEUE_FILE: &str = "download_queue.json";
+
+/// Maps to download once BeatSaver can be reached again, oldest first.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct DownloadQueue {
+    pub maps: Vec<Map>,
+}
+
+impl DownloadQueue {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        read_json(QUEUE_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(QUEUE_FILE, self)
+    }
+
+    pub fn push(&mut self, map: &Map) {
+        if !self.maps.iter().any(|m| m.id == map.id) {
+            self.maps.push(map.clone());
+        }
+    }
+
+    /// Empties the queue, returning the maps that were in it.
+    pub fn take(&mut self) -> Vec<Map> {
+        std::mem::take(&mut self.maps)
+    }
+}
@@ -0,0 +1,85 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use chrono::Utc;
+
+use crate::types::map::Map;
+
+use super::data_dir;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Where exported map lists are written, inside the data dir.
+pub fn exports_dir() -> PathBuf {
+    data_dir().join("exports")
+}
+
+/// Writes the maps to a new file named after the current time, returning its path.
+/// JSON exports contain the maps as BeatSaver returned them, CSV exports a summary line per map.
+pub fn export_maps(maps: &[Map], format: ExportFormat) -> Result<PathBuf, Box<dyn Error>> {
+    let content = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(maps)?,
+        ExportFormat::Csv => to_csv(maps),
+    };
+
+    let dir = exports_dir();
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!(
+        "maps-{}.{}",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        format.extension()
+    ));
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+fn to_csv(maps: &[Map]) -> String {
+    let mut csv = String::from(
+        "id,song_name,song_sub_name,song_author,mapper,bpm,duration,rating,upvotes,downvotes,hash\n",
+    );
+
+    for map in maps {
+        let fields = [
+            map.id.clone(),
+            map.metadata.song_name.clone(),
+            map.metadata.song_sub_name.clone(),
+            map.metadata.song_author_name.clone(),
+            map.metadata.level_author_name.clone(),
+            map.metadata.bpm.to_string(),
+            map.metadata.duration.to_string(),
+            format!("{:.3}", map.stats.score),
+            map.stats.upvotes.to_string(),
+            map.stats.downvotes.to_string(),
+            map.published_version()
+                .map(|version| version.hash.clone())
+                .unwrap_or_default(),
+        ];
+
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes the field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
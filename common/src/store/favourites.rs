@@ -0,0 +1,70 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+use super::{read_json, write_json};
+
+const FAVOURITES_FILE: &str = "favourites.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FavouriteEntry {
+    pub map: Map,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Maps the user has marked as favourites, most recently added first.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Favourites {
+    pub entries: Vec<FavouriteEntry>,
+}
+
+impl Favourites {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        read_json(FAVOURITES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(FAVOURITES_FILE, self)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.entries.iter().any(|entry| entry.map.id == id)
+    }
+
+    pub fn add(&mut self, map: &Map) {
+        if self.contains(&map.id) {
+            return;
+        }
+
+        self.entries.insert(
+            0,
+            FavouriteEntry {
+                map: map.clone(),
+                added_at: Utc::now(),
+            },
+        );
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.retain(|entry| entry.map.id != id);
+    }
+
+    /// Adds the map if it isn't a favourite yet, removes it otherwise.
+    /// Returns whether the map is a favourite afterwards.
+    pub fn toggle(&mut self, map: &Map) -> bool {
+        if self.contains(&map.id) {
+            self.remove(&map.id);
+            false
+        } else {
+            self.add(map);
+            true
+        }
+    }
+
+    pub fn maps(&self) -> Vec<Map> {
+        self.entries.iter().map(|entry| entry.map.clone()).collect()
+    }
+}
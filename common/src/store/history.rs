@@ -0,0 +1,54 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+use super::{read_json, write_json};
+
+const HISTORY_FILE: &str = "history.json";
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub map: Map,
+    pub viewed_at: DateTime<Utc>,
+}
+
+/// Recently viewed maps, most recent first.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        read_json(HISTORY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(HISTORY_FILE, self)
+    }
+
+    /// Moves the map to the top of the history, keeping at most `MAX_ENTRIES` entries.
+    pub fn record(&mut self, map: &Map) {
+        self.entries.retain(|entry| entry.map.id != map.id);
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                map: map.clone(),
+                viewed_at: Utc::now(),
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.retain(|entry| entry.map.id != id);
+    }
+
+    pub fn maps(&self) -> Vec<Map> {
+        self.entries.iter().map(|entry| entry.map.clone()).collect()
+    }
+}
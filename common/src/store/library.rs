@@ -0,0 +1,52 @@
+use std::{error::Error, fs};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+use super::{downloads_dir, read_json, write_json};
+
+const LIBRARY_FILE: &str = "library.json";
+
+/// Maps downloaded into the downloads dir, most recently downloaded first, so they can be
+/// searched without BeatSaver.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Library {
+    pub maps: Vec<Map>,
+}
+
+impl Library {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        read_json(LIBRARY_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(LIBRARY_FILE, self)
+    }
+
+    pub fn add(&mut self, map: &Map) {
+        self.maps.retain(|m| m.id != map.id);
+        self.maps.insert(0, map.clone());
+    }
+
+    /// The maps whose zip is still in the downloads dir, see `api::download::download_map`
+    /// for how they're named.
+    pub fn scan(&self) -> Vec<Map> {
+        let files: Vec<String> = fs::read_dir(downloads_dir())
+            .map(|dir| {
+                dir.flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.maps
+            .iter()
+            .filter(|map| {
+                let prefix = format!("{} (", map.id);
+                files.iter().any(|file| file.starts_with(&prefix))
+            })
+            .cloned()
+            .collect()
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{api::beatsaver::SearchQuery, types::map::Map};
+
+use self::{favourites::Favourites, history::History, library::Library};
+
+pub mod cache;
+pub mod download_queue;
+pub mod export;
+pub mod favourites;
+pub mod history;
+pub mod library;
+pub mod playlist;
+pub mod searches;
+
+const APP_DIR: &str = "bs-browser";
+
+/// Where persistent app data lives, e.g. `~/.local/share/bs-browser` on Linux.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR)
+}
+
+/// Where user configuration lives, e.g. `~/.config/bs-browser` on Linux.
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR)
+}
+
+/// Where cached API responses live, e.g. `~/.cache/bs-browser` on Linux.
+pub fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_DIR)
+}
+
+/// Where downloaded maps are saved, inside the data dir.
+pub fn downloads_dir() -> PathBuf {
+    data_dir().join("downloads")
+}
+
+/// Every map stored locally, i.e. the favourites followed by the viewing history and the
+/// downloaded maps, without duplicates.
+pub fn local_maps() -> Vec<Map> {
+    let mut maps = Favourites::load()
+        .map(|favourites| favourites.maps())
+        .unwrap_or_default();
+
+    let history = History::load()
+        .map(|history| history.maps())
+        .unwrap_or_default();
+    let library = Library::load()
+        .map(|library| library.scan())
+        .unwrap_or_default();
+
+    for map in history.into_iter().chain(library) {
+        if !maps.iter().any(|m| m.id == map.id) {
+            maps.push(map);
+        }
+    }
+    maps
+}
+
+/// Searches the locally stored maps, used when BeatSaver can't be reached.
+/// A map matches if it has the query's tags and every word of the query appears
+/// in its id, names or mapper.
+pub fn search_local(query: &SearchQuery) -> Vec<Map> {
+    let words: Vec<String> = query
+        .text
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+
+    local_maps()
+        .into_iter()
+        .filter(|m| {
+            let line = format!(
+                "{} {} {} {} {}",
+                m.id,
+                m.metadata.song_name,
+                m.metadata.song_sub_name,
+                m.metadata.song_author_name,
+                m.metadata.level_author_name
+            )
+            .to_lowercase();
+
+            words.iter().all(|word| line.contains(word))
+                && query.include_tags.iter().all(|tag| m.has_tag(tag))
+                && !query.exclude_tags.iter().any(|tag| m.has_tag(tag))
+        })
+        .collect()
+}
+
+/// Reads a JSON file from the data dir, falling back to the default value if it doesn't exist yet.
+pub(crate) fn read_json<T: DeserializeOwned + Default>(file: &str) -> Result<T, Box<dyn Error>> {
+    let path = data_dir().join(file);
+
+    if !path.exists() {
+        return Ok(T::default());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Writes a JSON file to the data dir through a temporary file so a crash can't truncate it.
+pub(crate) fn write_json<T: Serialize>(file: &str, value: &T) -> Result<(), Box<dyn Error>> {
+    write_data_file(file, serde_json::to_string(value)?.as_bytes())
+}
+
+/// Writes a file to the data dir through a temporary file so a crash can't truncate it.
+pub fn write_data_file(file: &str, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)?;
+
+    write_atomic(&dir.join(file), contents)
+}
+
+/// Writes the file next to it first and renames it into place, so readers never see a
+/// partly written file.
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut tmp_path = path.to_owned().into_os_string();
+    tmp_path.push(".tmp");
+
+    fs::write(&tmp_path, contents)?;
+    fs::rename(tmp_path, path)?;
+    Ok(())
+}
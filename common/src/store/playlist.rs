@@ -0,0 +1,70 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::map::Map;
+
+use super::{read_json, write_json};
+
+const PLAYLIST_FILE: &str = "playlist.bplist";
+
+const PLAYLIST_TITLE: &str = "bs-browser";
+
+/// Maps collected for playing in Beat Saber, stored in the game's `.bplist` format so the file can
+/// be copied into its `Playlists` folder.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Playlist {
+    pub playlist_title: String,
+    #[serde(default)]
+    pub playlist_author: String,
+    #[serde(default)]
+    pub songs: Vec<PlaylistSong>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistSong {
+    pub key: String,
+    pub hash: String,
+    pub song_name: String,
+}
+
+impl Default for Playlist {
+    fn default() -> Self {
+        Self {
+            playlist_title: PLAYLIST_TITLE.to_owned(),
+            playlist_author: String::new(),
+            songs: Vec::new(),
+        }
+    }
+}
+
+impl Playlist {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        read_json(PLAYLIST_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(PLAYLIST_FILE, self)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.songs.iter().any(|song| song.key == id)
+    }
+
+    /// Adds the map's published version, returning whether it wasn't in the playlist yet.
+    pub fn add(&mut self, map: &Map) -> bool {
+        let version = match map.published_version() {
+            Some(version) if !self.contains(&map.id) => version,
+            _ => return false,
+        };
+
+        self.songs.push(PlaylistSong {
+            key: map.id.clone(),
+            hash: version.hash.to_lowercase(),
+            song_name: map.metadata.song_name.clone(),
+        });
+        true
+    }
+}
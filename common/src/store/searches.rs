@@ -0,0 +1,100 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::{
+        beatsaver::{SearchOrder, SearchQuery},
+        paginator::{PageSource, Paginator},
+    },
+    filter::Filter,
+    sort::Sort,
+};
+
+use super::{read_json, write_json};
+
+const SEARCHES_FILE: &str = "searches.json";
+
+/// Number of past queries kept in the search history.
+const MAX_HISTORY: usize = 100;
+
+/// Past queries and the searches the user saved under a name.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Searches {
+    /// Most recent first.
+    pub history: Vec<String>,
+    pub saved: Vec<SavedSearch>,
+}
+
+/// A search that can be run again later, together with how its results were filtered and sorted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub filter: String,
+    #[serde(default)]
+    pub sort: Vec<Sort>,
+    /// Whether to tell the user about maps published since the search was last run.
+    #[serde(default)]
+    pub notify: bool,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+impl Searches {
+    pub fn load() -> Result<Searches, Box<dyn Error>> {
+        read_json(SEARCHES_FILE)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_json(SEARCHES_FILE, self)
+    }
+
+    /// Moves the query to the front of the history, adding it if it's new.
+    pub fn record(&mut self, query: &str) {
+        if query.trim().is_empty() {
+            return;
+        }
+
+        self.history.retain(|q| q != query);
+        self.history.insert(0, query.to_owned());
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    /// Saves the search, replacing any saved search with the same name.
+    pub fn add(&mut self, search: SavedSearch) {
+        match self.saved.iter_mut().find(|s| s.name == search.name) {
+            Some(saved) => *saved = search,
+            None => self.saved.push(search),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.saved.retain(|s| s.name != name);
+    }
+}
+
+impl SavedSearch {
+    /// Counts the maps matching the search that were published since it was last run.
+    /// Only the newest page of results is looked at.
+    pub async fn count_new(&self) -> Result<usize, Box<dyn Error>> {
+        let last_run = match self.last_run {
+            Some(last_run) => last_run,
+            None => return Ok(0),
+        };
+
+        let query = SearchQuery::parse(&self.query).order(SearchOrder::Latest);
+        let maps = Paginator::new(PageSource::Search(query))
+            .fetch_next()
+            .await?;
+        let filter = Filter::parse(&self.filter)?;
+
+        Ok(maps
+            .iter()
+            .filter(|m| m.last_published_at > last_run && filter.matches(m))
+            .count())
+    }
+}
@@ -1,11 +1,16 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Map {
     pub id: String,
     pub name: String,
-    pub last_published_at: String,
+    pub last_published_at: DateTime<Utc>,
+    #[serde(default)]
+    pub uploaded: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
     pub metadata: Metadata,
     pub stats: Stats,
     pub description: String,
@@ -13,9 +18,121 @@ pub struct Map {
     pub qualified: bool,
     pub versions: Vec<Version>,
     pub automapper: bool,
+    #[serde(default)]
+    pub declared_ai: DeclaredAi,
+    #[serde(default)]
+    pub uploader: Uploader,
+    #[serde(default)]
+    pub curator: Option<Uploader>,
+    #[serde(default)]
+    pub curated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl Map {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// The published version, falling back to the newest one for maps that were never published.
+    pub fn published_version(&self) -> Option<&Version> {
+        self.versions
+            .iter()
+            .find(|version| version.state == VersionState::Published)
+            .or(self.versions.first())
+    }
+
+    /// Highest notes per second of all difficulties of the published version.
+    pub fn max_nps(&self) -> f32 {
+        self.published_version()
+            .map(|version| {
+                version
+                    .diffs
+                    .iter()
+                    .map(|diff| diff.nps)
+                    .fold(0.0, f32::max)
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Highest ScoreSaber stars of all ranked difficulties of the published version.
+    pub fn max_stars(&self) -> Option<f32> {
+        self.published_version()?
+            .diffs
+            .iter()
+            .filter_map(|diff| diff.stars)
+            .reduce(f32::max)
+    }
+}
+
+/// BeatSaver tags either describe how a map plays or the genre of its song.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagKind {
+    Style,
+    Genre,
+}
+
+const STYLE_TAGS: [&str; 9] = [
+    "accuracy",
+    "balanced",
+    "challenge",
+    "dance-style",
+    "fitness",
+    "speed",
+    "tech",
+    "poodles",
+    "streams",
+];
+
+/// Tells style tags apart from genre tags. Tags BeatSaver adds later count as genres.
+pub fn tag_kind(tag: &str) -> TagKind {
+    if STYLE_TAGS.contains(&tag) {
+        TagKind::Style
+    } else {
+        TagKind::Genre
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Uploader {
+    pub id: i32,
+    pub name: String,
+    #[serde(default)]
+    pub avatar: String,
+    #[serde(default)]
+    pub verified_mapper: bool,
+    /// Only included when fetching the uploader directly, not when nested in a map.
+    #[serde(default)]
+    pub stats: Option<UploaderStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UploaderStats {
+    pub total_maps: i32,
+    pub ranked_maps: i32,
+    pub total_upvotes: i32,
+    pub total_downvotes: i32,
+    /// Average rating of the uploader's maps between 0 and 1.
+    pub avg_score: f32,
+    pub avg_bpm: f32,
+}
+
+/// Who declared the map as made by an automapper, if anyone.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeclaredAi {
+    #[default]
+    None,
+    Admin,
+    Uploader,
+    SageScore,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
     pub song_name: String,
@@ -26,27 +143,53 @@ pub struct Metadata {
     pub duration: i32,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Stats {
     pub downvotes: i32,
     pub upvotes: i32,
+    #[serde(default)]
+    pub plays: i32,
+    #[serde(default)]
+    pub downloads: i32,
+    /// Rating between 0 and 1, weighted by the number of votes.
+    #[serde(default)]
+    pub score: f32,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Version {
-    #[serde(rename(deserialize = "downloadURL"))]
+    #[serde(rename = "downloadURL")]
     pub download_url: String,
-    #[serde(rename(deserialize = "previewURL"))]
+    #[serde(rename = "previewURL")]
     pub preview_url: String,
-    #[serde(rename(deserialize = "coverURL"))]
+    #[serde(rename = "coverURL")]
     pub cover_url: String,
     pub diffs: Vec<MapDifficulty>,
     pub hash: String,
+    #[serde(default)]
+    pub state: VersionState,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sage_score: Option<i32>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "PascalCase")]
+pub enum VersionState {
+    Uploaded,
+    Testplay,
+    #[default]
+    Published,
+    Feedback,
+    Scheduled,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MapDifficulty {
     pub notes: i32,
@@ -55,9 +198,70 @@ pub struct MapDifficulty {
     pub difficulty: String,
     pub njs: f32,
     pub nps: f32,
+    /// Length in beats.
+    #[serde(default)]
+    pub length: f32,
+    #[serde(default)]
+    pub seconds: f32,
+    #[serde(default)]
+    pub offset: f32,
+    #[serde(default)]
+    pub events: i32,
+    #[serde(default)]
+    pub obstacles: i32,
+    #[serde(default)]
+    pub chroma: bool,
+    #[serde(default)]
+    pub ne: bool,
+    #[serde(default)]
+    pub me: bool,
+    #[serde(default)]
+    pub cinema: bool,
+    #[serde(default)]
+    pub parity_summary: ParitySummary,
+    #[serde(default)]
+    pub max_score: i32,
+    /// Custom difficulty name set by the mapper.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// ScoreSaber stars, only present for ranked difficulties.
+    #[serde(default)]
+    pub stars: Option<f32>,
+}
+
+impl MapDifficulty {
+    /// Names of the mods the difficulty requires or suggests.
+    pub fn mods(&self) -> Vec<&'static str> {
+        [
+            (self.chroma, "Chroma"),
+            (self.ne, "NE"),
+            (self.me, "ME"),
+            (self.cinema, "Cinema"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ParitySummary {
+    pub errors: i32,
+    pub warns: i32,
+    pub resets: i32,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct Maps {
     pub docs: Vec<Map>,
+    /// Only included in search responses.
+    #[serde(default)]
+    pub info: Option<SearchInfo>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct SearchInfo {
+    pub total: i32,
+    pub pages: i32,
 }
@@ -0,0 +1,38 @@
+use std::{collections::HashMap, error::Error, fs, path::PathBuf};
+
+use common::{api::beatsaver::Feed, store::config_dir};
+use serde::Deserialize;
+
+use crate::theme::ThemeConfig;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// User settings read from `config.toml` in the config dir, e.g. `~/.config/bs-browser`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    /// Feed the browser opens on: "latest", "curated", "top_rated" or "most_played". Opens on
+    /// search if unset.
+    pub default_feed: Option<Feed>,
+    /// Theme to draw the UI with: "dark", "light", "high-contrast", "monochrome" or the name of
+    /// one of `themes`. Monochrome is used regardless when `NO_COLOR` is set.
+    pub theme: Option<String>,
+    /// User themes by name, e.g. `[themes.mine]` with `base = "light"` and `header = "red"`.
+    pub themes: HashMap<String, ThemeConfig>,
+    /// "off", "error", "warn", "info", "debug" or "trace", info if unset.
+    pub log_level: Option<String>,
+    /// File to log to, `log.txt` in the data dir if unset.
+    pub log_file: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn load() -> Result<Config, Box<dyn Error>> {
+        let path = config_dir().join(CONFIG_FILE);
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+}
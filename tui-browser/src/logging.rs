@@ -0,0 +1,137 @@
+use std::{
+    collections::VecDeque,
+    env,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::{Mutex, MutexGuard},
+};
+
+use common::store::data_dir;
+use log::LevelFilter;
+use simplelog::{CombinedLogger, ConfigBuilder, SharedLogger, WriteLogger};
+
+use crate::config::Config;
+
+const LEVEL_VAR: &str = "BS_BROWSER_LOG";
+const FILE_VAR: &str = "BS_BROWSER_LOG_FILE";
+
+/// Most lines kept in memory for the log panel.
+const MAX_TAIL_LINES: usize = 1000;
+
+static TAIL: Mutex<LogTail> = Mutex::new(LogTail {
+    lines: VecDeque::new(),
+    partial: String::new(),
+});
+
+/// Where the log goes and how much of it, from the first of the command line flags, the
+/// environment and the config that sets them.
+#[derive(Debug)]
+pub struct LogSettings {
+    pub level: LevelFilter,
+    pub file: PathBuf,
+}
+
+impl LogSettings {
+    /// Reads `--log-level <level>` and `--log-file <path>` from the command line, falling back
+    /// to `BS_BROWSER_LOG` and `BS_BROWSER_LOG_FILE`, then to the config.
+    pub fn resolve(config: &Config) -> Result<LogSettings, Box<dyn Error>> {
+        let mut level = None;
+        let mut file = None;
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+
+            let slot = match flag.as_str() {
+                "--log-level" => &mut level,
+                "--log-file" => &mut file,
+                _ => return Err(io::Error::other(format!("Unknown argument {}", flag)).into()),
+            };
+
+            let value = value
+                .or_else(|| args.next())
+                .ok_or_else(|| io::Error::other(format!("{} needs a value", flag)))?;
+            *slot = Some(value);
+        }
+
+        let level = level
+            .or_else(|| env::var(LEVEL_VAR).ok())
+            .or_else(|| config.log_level.clone());
+        let file = file
+            .map(PathBuf::from)
+            .or_else(|| env::var_os(FILE_VAR).map(PathBuf::from))
+            .or_else(|| config.log_file.clone());
+
+        Ok(LogSettings {
+            level: match level {
+                Some(level) => LevelFilter::from_str(&level)
+                    .map_err(|_| io::Error::other(format!("Unknown log level {:?}", level)))?,
+                None => LevelFilter::Info,
+            },
+            file: file.unwrap_or_else(|| data_dir().join("log.txt")),
+        })
+    }
+}
+
+/// Starts logging to the settings' file and to the tail the log panel shows.
+pub fn init(settings: &LogSettings) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = settings.file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = fs::File::create(&settings.file)?;
+
+    // the target tells HTTP requests apart, see `common::api::fetch_bytes`
+    let config = ConfigBuilder::new()
+        .set_target_level(LevelFilter::Error)
+        .build();
+    let loggers: Vec<Box<dyn SharedLogger>> = vec![
+        WriteLogger::new(settings.level, config.clone(), file),
+        WriteLogger::new(settings.level, config, TailWriter),
+    ];
+    CombinedLogger::init(loggers)?;
+    Ok(())
+}
+
+struct LogTail {
+    lines: VecDeque<String>,
+    /// The line being written, until its newline comes.
+    partial: String,
+}
+
+fn lock_tail() -> MutexGuard<'static, LogTail> {
+    TAIL.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The latest lines of the log, oldest first.
+pub fn tail() -> Vec<String> {
+    lock_tail().lines.iter().cloned().collect()
+}
+
+/// Keeps the latest log lines in memory.
+struct TailWriter;
+
+impl Write for TailWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut tail = lock_tail();
+        tail.partial.push_str(&String::from_utf8_lossy(buf));
+
+        while let Some(end) = tail.partial.find('\n') {
+            let line: String = tail.partial.drain(..=end).collect();
+            tail.lines.push_back(line.trim_end().to_owned());
+            if tail.lines.len() > MAX_TAIL_LINES {
+                tail.lines.pop_front();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,43 +1,78 @@
-use std::{fs, io};
+use std::{io, process};
 
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-
-use log::LevelFilter;
-use simplelog::WriteLogger;
+use common::store::cache;
+use config::Config;
+use log::{error, info};
+use logging::LogSettings;
+use table_layout::TableLayout;
+use theme::{set_theme, Theme};
 use tui::{backend::CrosstermBackend, Terminal};
-use ui::map_browser::start_browser;
+use ui::{
+    map_browser::start_browser,
+    notifications::{notify, Severity},
+};
+use utils::terminal::{handle_signals, install_panic_hook, TerminalGuard};
 
+mod config;
+mod logging;
+mod table_layout;
+mod theme;
 mod ui;
 mod utils;
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
-    let log_file = fs::File::create("log.txt").unwrap();
+    let (config, config_error) = match Config::load() {
+        Ok(config) => (config, None),
+        Err(e) => (Config::default(), Some(e)),
+    };
 
-    WriteLogger::init(LevelFilter::Info, simplelog::Config::default(), log_file).unwrap();
+    // nothing is drawn yet, so these still go to the terminal
+    let log_settings = LogSettings::resolve(&config).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("Usage: tui-browser [--log-level <level>] [--log-file <path>]");
+        process::exit(2);
+    });
+    if let Err(e) = logging::init(&log_settings) {
+        eprintln!("Failed to log to {}: {}", log_settings.file.display(), e);
+    }
+    info!(
+        "Logging at {} to {}",
+        log_settings.level,
+        log_settings.file.display()
+    );
 
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    if let Some(e) = config_error {
+        let message = format!("Failed to load config, using defaults: {}", e);
+        error!("{}", message);
+        notify(Severity::Warning, message);
+    }
+    set_theme(Theme::load(&config).unwrap_or_else(|e| {
+        let message = format!("Failed to load theme, using the dark one: {}", e);
+        error!("{}", message);
+        notify(Severity::Warning, message);
+        Theme::dark()
+    }));
+    let layout = TableLayout::load().unwrap_or_else(|e| {
+        let message = format!("Failed to load table layout, using defaults: {}", e);
+        error!("{}", message);
+        notify(Severity::Warning, message);
+        TableLayout::default()
+    });
 
-    terminal.clear()?;
+    match cache::prune() {
+        Ok(removed) => info!("Removed {} old cache entries", removed),
+        Err(e) => error!("Failed to prune the cache: {}", e),
+    }
 
-    start_browser(&mut terminal).await?;
+    install_panic_hook();
+    handle_signals();
 
-    disable_raw_mode()?;
+    let _guard = TerminalGuard::new()?;
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend)?;
 
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    terminal.clear()?;
 
-    Ok(())
+    start_browser(&mut terminal, &config, layout).await
 }
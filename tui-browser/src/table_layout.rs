@@ -0,0 +1,44 @@
+use std::{error::Error, fs};
+
+use common::{
+    sort::Sort,
+    store::{data_dir, write_data_file},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ui::map_table::ColumnLayout;
+
+const LAYOUT_FILE: &str = "table.toml";
+
+/// Columns and sort order of the browser table, saved whenever the user changes them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TableLayout {
+    pub columns: Vec<ColumnLayout>,
+    pub sort: Vec<Sort>,
+}
+
+impl Default for TableLayout {
+    fn default() -> Self {
+        TableLayout {
+            columns: ColumnLayout::defaults(),
+            sort: Vec::new(),
+        }
+    }
+}
+
+impl TableLayout {
+    pub fn load() -> Result<TableLayout, Box<dyn Error>> {
+        let path = data_dir().join(LAYOUT_FILE);
+
+        if !path.exists() {
+            return Ok(TableLayout::default());
+        }
+
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        write_data_file(LAYOUT_FILE, toml::to_string(self)?.as_bytes())
+    }
+}
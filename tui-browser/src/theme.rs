@@ -0,0 +1,285 @@
+use std::{collections::HashMap, env, error::Error, io, sync::OnceLock};
+
+use serde::Deserialize;
+use tui::style::{Color, Modifier, Style};
+
+use crate::config::Config;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Styles of the UI by what they're used for.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Table headers, help keys and the active tab.
+    pub header: Style,
+    /// Labels in front of values and other secondary highlights.
+    pub label: Style,
+    /// The selected row of a table.
+    pub highlight: Style,
+    /// Text being typed.
+    pub input: Style,
+    /// Song and mapper names on top of their screens.
+    pub title: Style,
+    /// Characters matched by the filter.
+    pub matched: Style,
+    /// Rows marked for batch actions.
+    pub marked: Style,
+    /// Ranked and qualified badges.
+    pub ranked: Style,
+    pub error: Style,
+    pub warning: Style,
+    /// Good news like new maps of saved searches.
+    pub success: Style,
+    /// Links in map descriptions.
+    pub link: Style,
+    pub style_tag: Style,
+    pub genre_tag: Style,
+    pub easy: Style,
+    pub normal: Style,
+    pub hard: Style,
+    pub expert: Style,
+    pub expert_plus: Style,
+}
+
+/// A user theme from the config: a built-in theme to start from and the colors of the roles
+/// to change, e.g. `header = "yellow"` or `expert_plus = "#ff00ff"`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub base: Option<String>,
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            header: fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            label: fg(Color::Cyan),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            input: fg(Color::Magenta),
+            title: fg(Color::LightMagenta).add_modifier(Modifier::BOLD),
+            matched: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            marked: fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ranked: fg(Color::Green),
+            error: fg(Color::Red),
+            warning: fg(Color::Yellow),
+            success: fg(Color::Green).add_modifier(Modifier::BOLD),
+            link: fg(Color::LightBlue).add_modifier(Modifier::UNDERLINED),
+            style_tag: fg(Color::Yellow),
+            genre_tag: fg(Color::LightBlue),
+            easy: fg(Color::Green),
+            normal: fg(Color::Blue),
+            hard: fg(Color::Rgb(255, 99, 71)),
+            expert: fg(Color::Red),
+            expert_plus: fg(Color::Magenta),
+        }
+    }
+
+    /// For terminals with a light background, avoiding yellows and light colors.
+    pub fn light() -> Theme {
+        Theme {
+            header: fg(Color::Blue).add_modifier(Modifier::BOLD),
+            label: fg(Color::Blue),
+            input: fg(Color::Magenta),
+            title: fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            matched: fg(Color::Red).add_modifier(Modifier::BOLD),
+            marked: fg(Color::Rgb(175, 95, 0)).add_modifier(Modifier::BOLD),
+            warning: fg(Color::Rgb(175, 95, 0)),
+            style_tag: fg(Color::Rgb(175, 95, 0)),
+            genre_tag: fg(Color::Blue),
+            link: fg(Color::Blue).add_modifier(Modifier::UNDERLINED),
+            hard: fg(Color::Rgb(200, 70, 40)),
+            ..Theme::dark()
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            header: fg(Color::LightCyan).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            label: fg(Color::LightCyan),
+            highlight: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            input: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            title: fg(Color::White).add_modifier(Modifier::BOLD),
+            matched: fg(Color::LightYellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            marked: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            ranked: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            error: fg(Color::LightRed).add_modifier(Modifier::BOLD),
+            warning: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            success: fg(Color::LightGreen).add_modifier(Modifier::BOLD),
+            link: fg(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+            style_tag: fg(Color::LightYellow),
+            genre_tag: fg(Color::LightCyan),
+            easy: fg(Color::LightGreen),
+            normal: fg(Color::LightBlue),
+            hard: fg(Color::LightYellow),
+            expert: fg(Color::LightRed),
+            expert_plus: fg(Color::LightMagenta),
+        }
+    }
+
+    /// No colors at all, only bold, underlined and reversed text. Used when `NO_COLOR` is set.
+    pub fn monochrome() -> Theme {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        Theme {
+            header: bold,
+            label: Style::default(),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            input: bold,
+            title: bold,
+            matched: Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            marked: Style::default().add_modifier(Modifier::UNDERLINED),
+            ranked: bold,
+            error: bold,
+            warning: bold,
+            success: bold,
+            link: Style::default().add_modifier(Modifier::UNDERLINED),
+            style_tag: Style::default(),
+            genre_tag: Style::default(),
+            easy: Style::default(),
+            normal: Style::default(),
+            hard: Style::default(),
+            expert: Style::default(),
+            expert_plus: Style::default(),
+        }
+    }
+
+    fn built_in(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "monochrome" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// The theme the config asks for, monochrome if the `NO_COLOR` environment variable is set.
+    pub fn load(config: &Config) -> Result<Theme, Box<dyn Error>> {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Ok(Theme::monochrome());
+        }
+
+        let name = match &config.theme {
+            Some(name) => name,
+            None => return Ok(Theme::dark()),
+        };
+
+        if let Some(theme) = Theme::built_in(name) {
+            return Ok(theme);
+        }
+
+        let user_theme = config
+            .themes
+            .get(name)
+            .ok_or_else(|| io::Error::other(format!("Unknown theme {:?}", name)))?;
+
+        let base = user_theme.base.as_deref().unwrap_or("dark");
+        let mut theme = Theme::built_in(base)
+            .ok_or_else(|| io::Error::other(format!("Unknown base theme {:?}", base)))?;
+
+        for (role, color) in &user_theme.colors {
+            let color = parse_color(color)
+                .ok_or_else(|| io::Error::other(format!("Invalid color {:?}", color)))?;
+
+            let style = theme
+                .role_mut(role)
+                .ok_or_else(|| io::Error::other(format!("Unknown theme role {:?}", role)))?;
+            *style = style.fg(color);
+        }
+        Ok(theme)
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Style> {
+        Some(match role {
+            "header" => &mut self.header,
+            "label" => &mut self.label,
+            "highlight" => &mut self.highlight,
+            "input" => &mut self.input,
+            "title" => &mut self.title,
+            "matched" => &mut self.matched,
+            "marked" => &mut self.marked,
+            "ranked" => &mut self.ranked,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "success" => &mut self.success,
+            "link" => &mut self.link,
+            "style_tag" => &mut self.style_tag,
+            "genre_tag" => &mut self.genre_tag,
+            "easy" => &mut self.easy,
+            "normal" => &mut self.normal,
+            "hard" => &mut self.hard,
+            "expert" => &mut self.expert,
+            "expert_plus" => &mut self.expert_plus,
+            _ => return None,
+        })
+    }
+
+    /// Style of a difficulty by its BeatSaver name, e.g. `ExpertPlus`.
+    pub fn difficulty(&self, difficulty: &str) -> Style {
+        match difficulty {
+            "Easy" => self.easy,
+            "Normal" => self.normal,
+            "Hard" => self.hard,
+            "Expert" => self.expert,
+            "ExpertPlus" => self.expert_plus,
+            _ => Style::default(),
+        }
+    }
+}
+
+fn fg(color: Color) -> Style {
+    Style::default().fg(color)
+}
+
+/// Parses a color name like `lightblue`, a hex color like `#ff6347` or a 256 color index.
+fn parse_color(color: &str) -> Option<Color> {
+    let color = color.trim().to_lowercase();
+
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    if let Ok(index) = color.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+
+    Some(match color.replace(['-', '_', ' '], "").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    })
+}
+
+/// Makes `theme` the one the UI is drawn with. Only the first call has an effect.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The theme the UI is drawn with, dark unless another one was set at startup.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
@@ -0,0 +1,229 @@
+use common::{
+    sort::{SortDirection, SortKey},
+    store::export::ExportFormat,
+};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::theme::theme;
+
+use super::{
+    help::centered_rect,
+    map_table::{highlighted, SortKeyColumn},
+};
+
+/// Most commands shown at once below the palette's input.
+const MAX_SHOWN: u16 = 12;
+
+/// Something the browser can do that's run from the command palette by name.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Search,
+    SortBy(SortKey, SortDirection),
+    Download,
+    AddToPlaylist,
+    Favourite,
+    Export(ExportFormat),
+    QueuePreviews,
+    MarkAll,
+    OpenInBrowser,
+    SavedSearches,
+    Columns,
+    Messages,
+    Log,
+    Help,
+}
+
+impl Command {
+    /// Every command of the browser, in the order they're listed before anything is typed.
+    pub fn all() -> Vec<Command> {
+        let mut commands = vec![
+            Command::Search,
+            Command::Download,
+            Command::AddToPlaylist,
+            Command::Favourite,
+            Command::Export(ExportFormat::Json),
+            Command::Export(ExportFormat::Csv),
+            Command::QueuePreviews,
+            Command::MarkAll,
+            Command::OpenInBrowser,
+            Command::SavedSearches,
+            Command::Columns,
+            Command::Messages,
+            Command::Log,
+            Command::Help,
+        ];
+
+        for key in SortKey::ALL {
+            commands.push(Command::SortBy(key, SortDirection::Ascending));
+            commands.push(Command::SortBy(key, SortDirection::Descending));
+        }
+        commands
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Command::Search => "Search".to_owned(),
+            Command::SortBy(key, direction) => format!(
+                "Sort by {} {}",
+                key.column().name(),
+                match direction {
+                    SortDirection::Ascending => "ascending",
+                    SortDirection::Descending => "descending",
+                }
+            ),
+            Command::Download => "Download the marked maps".to_owned(),
+            Command::AddToPlaylist => "Add the marked maps to the playlist".to_owned(),
+            Command::Favourite => "Add the marked maps to the favourites".to_owned(),
+            Command::Export(ExportFormat::Json) => "Export the marked maps as JSON".to_owned(),
+            Command::Export(ExportFormat::Csv) => "Export the marked maps as CSV".to_owned(),
+            Command::QueuePreviews => "Play the previews of the marked maps".to_owned(),
+            Command::MarkAll => "Mark or unmark all maps".to_owned(),
+            Command::OpenInBrowser => "Open the selected map in the web browser".to_owned(),
+            Command::SavedSearches => "Saved searches".to_owned(),
+            Command::Columns => "Configure the columns".to_owned(),
+            Command::Messages => "Messages".to_owned(),
+            Command::Log => "Show the log".to_owned(),
+            Command::Help => "Help".to_owned(),
+        }
+    }
+}
+
+/// Finds commands by fuzzy matching what's typed against their labels.
+pub struct CommandPalette {
+    pub input: String,
+    commands: Vec<Command>,
+    /// Commands matching the input, best first, with the matched characters of their labels.
+    matches: Vec<(Command, Vec<usize>)>,
+    table_state: TableState,
+    matcher: SkimMatcherV2,
+}
+
+impl CommandPalette {
+    pub fn new(commands: Vec<Command>) -> CommandPalette {
+        let mut palette = CommandPalette {
+            input: String::new(),
+            commands,
+            matches: Vec::new(),
+            table_state: TableState::default(),
+            matcher: SkimMatcherV2::default().ignore_case(),
+        };
+        palette.update();
+        palette
+    }
+
+    /// Clears the input so every command is listed again.
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.update();
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+        self.update();
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+        self.update();
+    }
+
+    fn update(&mut self) {
+        let mut matches: Vec<(i64, Command, Vec<usize>)> = self
+            .commands
+            .iter()
+            .filter_map(|command| {
+                let (score, indices) = self.matcher.fuzzy_indices(&command.label(), &self.input)?;
+                Some((score, *command, indices))
+            })
+            .collect();
+
+        // stable, so equally good matches keep the order of the commands
+        matches.sort_by_key(|(score, _, _)| -score);
+
+        self.matches = matches
+            .into_iter()
+            .map(|(_, command, indices)| (command, indices))
+            .collect();
+        self.table_state
+            .select((!self.matches.is_empty()).then_some(0));
+    }
+
+    pub fn selected(&self) -> Option<Command> {
+        self.matches
+            .get(self.table_state.selected()?)
+            .map(|(command, _)| *command)
+    }
+
+    pub fn next_item(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(i) if i + 1 < self.matches.len() => i + 1,
+            _ => 0,
+        };
+        self.table_state.select(Some(i));
+    }
+
+    pub fn previous_item(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(0) | None => self.matches.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.table_state.select(Some(i));
+    }
+}
+
+/// Draws the palette's input and the matching commands in the upper middle of `area`.
+pub fn draw_palette<B: Backend>(frame: &mut Frame<B>, palette: &mut CommandPalette, area: Rect) {
+    let shown = (palette.matches.len() as u16).clamp(1, MAX_SHOWN);
+    let mut popup = centered_rect(60, shown + 5, area);
+    popup.y = area.y;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .split(popup);
+
+    frame.render_widget(Clear, popup);
+
+    let input = Paragraph::new(palette.input.as_ref())
+        .block(Block::default().borders(Borders::ALL).title("Command"));
+    frame.render_widget(input, chunks[0]);
+    frame.set_cursor(
+        chunks[0].x + palette.input.width() as u16 + 1,
+        chunks[0].y + 1,
+    );
+
+    let rows: Vec<Row> = palette
+        .matches
+        .iter()
+        .map(|(command, indices)| Row::new(vec![highlighted(&command.label(), indices)]))
+        .collect();
+
+    let table = Table::new(rows)
+        .widths(&[Constraint::Percentage(100)])
+        .highlight_style(theme().highlight)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if palette.matches.is_empty() {
+                    "No matching commands"
+                } else {
+                    "Run(Enter) Select(↑/↓) Close(Esc)"
+                }),
+        );
+    frame.render_stateful_widget(table, chunks[1], &mut palette.table_state);
+}
@@ -0,0 +1,426 @@
+use tui::{
+    style::{Modifier, Style},
+    text::{Span, Spans},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::theme::theme;
+
+const BULLET: &str = "• ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SegmentKind {
+    Text,
+    Bold,
+    /// Index into the description's links.
+    Link(usize),
+}
+
+#[derive(Debug)]
+struct Segment {
+    text: String,
+    kind: SegmentKind,
+}
+
+#[derive(Debug, Default)]
+struct Line {
+    bullet: bool,
+    segments: Vec<Segment>,
+}
+
+/// A map description with the formatting mappers commonly use: line breaks, `-`/`*` bullet
+/// lists, `**bold**` text, bare URLs and `[label](url)` links. It scrolls and its links can be
+/// selected one after another to open them.
+#[derive(Debug, Default)]
+pub struct Description {
+    lines: Vec<Line>,
+    links: Vec<String>,
+    /// First shown line of the wrapped text.
+    scroll: usize,
+    selected_link: Option<usize>,
+    /// Whether the next draw should scroll the selected link into view.
+    reveal_link: bool,
+}
+
+impl Description {
+    pub fn parse(text: &str) -> Description {
+        let mut description = Description::default();
+
+        for raw_line in text.lines() {
+            let trimmed = raw_line.trim_start();
+            let (bullet, content) = match trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+                .or_else(|| trimmed.strip_prefix(BULLET))
+            {
+                Some(content) => (true, content),
+                None => (false, raw_line.trim_end()),
+            };
+
+            let segments = description.parse_segments(content);
+            description.lines.push(Line { bullet, segments });
+        }
+        description
+    }
+
+    /// Splits a line into bold, link and plain text segments.
+    fn parse_segments(&mut self, line: &str) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut bold = false;
+        let mut text = String::new();
+        let mut rest = line;
+
+        let flush = |text: &mut String, segments: &mut Vec<Segment>, bold: bool| {
+            if !text.is_empty() {
+                segments.push(Segment {
+                    text: std::mem::take(text),
+                    kind: if bold {
+                        SegmentKind::Bold
+                    } else {
+                        SegmentKind::Text
+                    },
+                });
+            }
+        };
+
+        while let Some(c) = rest.chars().next() {
+            // only `**`, since `__` shows up in names like `__init__`
+            if rest.starts_with("**") {
+                flush(&mut text, &mut segments, bold);
+                bold = !bold;
+                rest = &rest[2..];
+            } else if let Some((label, url, len)) = markdown_link(rest) {
+                flush(&mut text, &mut segments, bold);
+                segments.push(self.link(label, url));
+                rest = &rest[len..];
+            } else if rest.starts_with("http://") || rest.starts_with("https://") {
+                flush(&mut text, &mut segments, bold);
+                let url = bare_url(rest);
+                segments.push(self.link(url, url));
+                rest = &rest[url.len()..];
+            } else {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        flush(&mut text, &mut segments, bold);
+        segments
+    }
+
+    fn link(&mut self, label: &str, url: &str) -> Segment {
+        self.links.push(url.to_owned());
+        Segment {
+            text: label.to_owned(),
+            kind: SegmentKind::Link(self.links.len() - 1),
+        }
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll += lines;
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    pub fn next_link(&mut self) {
+        if self.links.is_empty() {
+            return;
+        }
+        self.selected_link = Some(match self.selected_link {
+            Some(i) if i + 1 < self.links.len() => i + 1,
+            _ => 0,
+        });
+        self.reveal_link = true;
+    }
+
+    pub fn previous_link(&mut self) {
+        if self.links.is_empty() {
+            return;
+        }
+        self.selected_link = Some(match self.selected_link {
+            Some(0) | None => self.links.len() - 1,
+            Some(i) => i - 1,
+        });
+        self.reveal_link = true;
+    }
+
+    pub fn selected_url(&self) -> Option<&str> {
+        self.links.get(self.selected_link?).map(String::as_str)
+    }
+
+    pub fn has_links(&self) -> bool {
+        !self.links.is_empty()
+    }
+
+    /// The lines shown in an area `width` wide and `height` high, keeping the scroll within
+    /// the text and the selected link in view.
+    pub fn visible_lines(&mut self, width: u16, height: u16) -> Vec<Spans<'static>> {
+        let wrapped = self.wrap(width as usize);
+        let height = height as usize;
+
+        if self.reveal_link {
+            self.reveal_link = false;
+            if let Some(line) = wrapped.iter().position(|(_, links)| {
+                self.selected_link
+                    .is_some_and(|selected| links.contains(&selected))
+            }) {
+                if line < self.scroll {
+                    self.scroll = line;
+                } else if line >= self.scroll + height {
+                    self.scroll = line + 1 - height;
+                }
+            }
+        }
+        self.scroll = self.scroll.min(wrapped.len().saturating_sub(height));
+
+        wrapped
+            .into_iter()
+            .skip(self.scroll)
+            .take(height)
+            .map(|(spans, _)| spans)
+            .collect()
+    }
+
+    fn style(&self, kind: SegmentKind) -> Style {
+        match kind {
+            SegmentKind::Text => Style::default(),
+            SegmentKind::Bold => Style::default().add_modifier(Modifier::BOLD),
+            SegmentKind::Link(i) if self.selected_link == Some(i) => {
+                theme().link.patch(theme().highlight)
+            }
+            SegmentKind::Link(_) => theme().link,
+        }
+    }
+
+    /// Word wraps the lines to `width`, with the links each wrapped line shows. Bullet points
+    /// keep the text of their continuation lines indented.
+    fn wrap(&self, width: usize) -> Vec<(Spans<'static>, Vec<usize>)> {
+        let width = width.max(BULLET.width() + 1);
+        let mut wrapped = Vec::new();
+
+        for line in &self.lines {
+            let indent = if line.bullet { BULLET.width() } else { 0 };
+            let mut spans = Vec::new();
+            let mut links = Vec::new();
+            let mut used = indent;
+            if line.bullet {
+                spans.push(Span::raw(BULLET));
+            }
+
+            for segment in &line.segments {
+                let style = self.style(segment.kind);
+
+                for word in split_words(&segment.text) {
+                    let blank = word.trim().is_empty();
+                    let word_width = word.width();
+
+                    if used + word_width > width && used > indent {
+                        wrapped.push((Spans::from(std::mem::take(&mut spans)), links));
+                        links = Vec::new();
+                        spans.push(Span::raw(" ".repeat(indent)));
+                        used = indent;
+                        if blank {
+                            continue;
+                        }
+                    }
+
+                    // words longer than a whole line, like long URLs, are broken anywhere
+                    let mut piece = String::new();
+                    for c in word.chars() {
+                        let char_width = c.width().unwrap_or(0);
+                        if used + char_width > width {
+                            spans.push(Span::styled(std::mem::take(&mut piece), style));
+                            if let SegmentKind::Link(i) = segment.kind {
+                                links.push(i);
+                            }
+                            wrapped.push((Spans::from(std::mem::take(&mut spans)), links));
+                            links = Vec::new();
+                            spans.push(Span::raw(" ".repeat(indent)));
+                            used = indent;
+                        }
+                        piece.push(c);
+                        used += char_width;
+                    }
+                    spans.push(Span::styled(piece, style));
+                    if let SegmentKind::Link(i) = segment.kind {
+                        links.push(i);
+                    }
+                }
+            }
+            wrapped.push((Spans::from(spans), links));
+        }
+        wrapped
+    }
+}
+
+/// Splits text into words and the runs of spaces between them.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut blank = None;
+
+    for (i, c) in text.char_indices() {
+        let is_blank = c.is_whitespace();
+        if blank.is_some_and(|blank| blank != is_blank) {
+            words.push(&text[start..i]);
+            start = i;
+        }
+        blank = Some(is_blank);
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+/// A URL at the start of `text`, up to whitespace and without trailing punctuation.
+fn bare_url(text: &str) -> &str {
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    text[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '"', '\''])
+}
+
+/// A `[label](url)` link at the start of `text`, with the length of its markup.
+fn markdown_link(text: &str) -> Option<(&str, &str, usize)> {
+    let label_end = text.strip_prefix('[')?.find("](")? + 1;
+    let label = &text[1..label_end];
+    let rest = &text[label_end + 2..];
+    let url_end = rest.find(')')?;
+    let url = &rest[..url_end];
+
+    if !(url.starts_with("http://") || url.starts_with("https://")) || label.contains('\n') {
+        return None;
+    }
+    Some((label, url, label_end + 2 + url_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segments(line: &Line) -> Vec<(&str, SegmentKind)> {
+        line.segments
+            .iter()
+            .map(|segment| (segment.text.as_str(), segment.kind))
+            .collect()
+    }
+
+    fn wrapped(text: &str, width: usize) -> Vec<String> {
+        Description::parse(text)
+            .wrap(width)
+            .into_iter()
+            .map(|(spans, _)| spans.0.iter().map(|span| span.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn parses_bold_and_keeps_underscores() {
+        let description = Description::parse("a **bold** word in __init__");
+
+        assert_eq!(
+            segments(&description.lines[0]),
+            [
+                ("a ", SegmentKind::Text),
+                ("bold", SegmentKind::Bold),
+                (" word in __init__", SegmentKind::Text),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_links() {
+        let description =
+            Description::parse("See https://example.com/a_b. Or [the wiki](https://wiki.example)!");
+
+        assert_eq!(
+            segments(&description.lines[0]),
+            [
+                ("See ", SegmentKind::Text),
+                ("https://example.com/a_b", SegmentKind::Link(0)),
+                (". Or ", SegmentKind::Text),
+                ("the wiki", SegmentKind::Link(1)),
+                ("!", SegmentKind::Text),
+            ]
+        );
+        assert_eq!(
+            description.links,
+            ["https://example.com/a_b", "https://wiki.example"]
+        );
+    }
+
+    #[test]
+    fn trims_trailing_punctuation_from_urls() {
+        assert_eq!(
+            bare_url("https://a.example/x), next"),
+            "https://a.example/x"
+        );
+        assert_eq!(
+            bare_url("https://a.example/?q=1\"."),
+            "https://a.example/?q=1"
+        );
+        assert_eq!(bare_url("https://a.example/x?y"), "https://a.example/x?y");
+    }
+
+    #[test]
+    fn only_takes_markdown_links_to_urls() {
+        assert_eq!(
+            markdown_link("[label](https://a.example) rest"),
+            Some(("label", "https://a.example", 26))
+        );
+        assert_eq!(markdown_link("[label](not a url)"), None);
+        assert_eq!(markdown_link("[label] (https://a.example)"), None);
+        assert_eq!(markdown_link("[unclosed](https://a.example"), None);
+    }
+
+    #[test]
+    fn parses_bullets() {
+        let description = Description::parse("- one\n  * two\n• three\n-not a bullet");
+
+        let bullets: Vec<_> = description.lines.iter().map(|line| line.bullet).collect();
+        assert_eq!(bullets, [true, true, true, false]);
+        assert_eq!(
+            segments(&description.lines[1]),
+            [("two", SegmentKind::Text)]
+        );
+    }
+
+    #[test]
+    fn wraps_words_and_indents_bullets() {
+        assert_eq!(
+            wrapped("the quick brown fox", 10),
+            ["the quick ", "brown fox"]
+        );
+        assert_eq!(
+            wrapped("- the quick brown fox", 10),
+            ["• the ", "  quick ", "  brown ", "  fox"]
+        );
+    }
+
+    #[test]
+    fn breaks_words_longer_than_a_line() {
+        assert_eq!(
+            wrapped("go https://example.com/long", 10),
+            ["go ", "https://ex", "ample.com/", "long"]
+        );
+
+        let description = Description::parse("go https://example.com/long");
+        let links: Vec<_> = description
+            .wrap(10)
+            .into_iter()
+            .map(|(_, links)| links)
+            .collect();
+        assert_eq!(links, [vec![], vec![0], vec![0], vec![0]]);
+    }
+
+    #[test]
+    fn scrolls_the_selected_link_into_view() {
+        let mut description = Description::parse("one\ntwo\nthree\nhttps://example.com");
+
+        description.next_link();
+        let lines = description.visible_lines(40, 2);
+
+        assert_eq!(description.selected_url(), Some("https://example.com"));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].0[0].content, "https://example.com");
+    }
+}
@@ -0,0 +1,66 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    widgets::{Block, Borders, Cell, Clear, Row, Table},
+    Frame,
+};
+
+use crate::theme::theme;
+
+/// An area of at most `width` x `height` in the middle of `area`.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Draws the keys of the current screen and what they do on top of it.
+pub fn draw_help<B: Backend>(frame: &mut Frame<B>, title: &str, entries: &[(String, String)]) {
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let action_width = entries
+        .iter()
+        .map(|(_, action)| action.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+
+    let title = format!("{} - press any key to close", title);
+    let width = (key_width + action_width + 5).max(title.chars().count() as u16 + 4);
+    let area = centered_rect(width, entries.len() as u16 + 2, frame.size());
+
+    let rows: Vec<Row> = entries
+        .iter()
+        .map(|(key, action)| {
+            Row::new(vec![
+                Cell::from(key.clone()).style(theme().header),
+                Cell::from(action.clone()),
+            ])
+        })
+        .collect();
+
+    let widths = [Constraint::Length(key_width), Constraint::Min(1)];
+    let table = Table::new(rows)
+        .widths(&widths)
+        .column_spacing(2)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(table, area);
+}
+
+/// Help entries from string literals.
+pub fn entries(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|(key, action)| (key.to_string(), action.to_string()))
+        .collect()
+}
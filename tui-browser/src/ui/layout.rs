@@ -0,0 +1,38 @@
+use tui::{
+    backend::Backend,
+    layout::Alignment,
+    text::{Span, Spans, Text},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::theme::theme;
+
+use super::help::centered_rect;
+
+/// Smallest terminal the screens are laid out for.
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 16;
+
+/// Draws a notice asking for a bigger terminal if the frame is smaller than
+/// [`MIN_WIDTH`] x [`MIN_HEIGHT`], returning whether it did so instead of the screen.
+pub fn draw_too_small<B: Backend>(frame: &mut Frame<B>) -> bool {
+    let size = frame.size();
+    if size.width >= MIN_WIDTH && size.height >= MIN_HEIGHT {
+        return false;
+    }
+
+    let text = Text::from(vec![
+        Spans::from(Span::styled("Terminal too small", theme().error)),
+        Spans::from(format!("{}x{}", size.width, size.height)),
+        Spans::from(format!("needs at least {}x{}", MIN_WIDTH, MIN_HEIGHT)),
+    ]);
+
+    frame.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        centered_rect(size.width, 3, size),
+    );
+    true
+}
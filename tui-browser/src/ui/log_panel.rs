@@ -0,0 +1,128 @@
+use std::{io, time::Duration};
+
+use crossterm::event::{self, poll, Event, KeyCode, KeyEventKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Style,
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    logging::tail,
+    theme::theme,
+    utils::terminal::{interrupt, is_interrupt},
+};
+
+use super::layout::draw_too_small;
+
+/// How often the panel checks for new lines.
+const REFRESH_RATE: Duration = Duration::from_millis(256);
+
+struct LogPanel {
+    /// How many lines the view is scrolled up from the newest one, following new lines at 0.
+    scroll: usize,
+    /// Whether only the HTTP requests are shown.
+    http_only: bool,
+}
+
+impl LogPanel {
+    fn lines(&self) -> Vec<String> {
+        tail()
+            .into_iter()
+            .filter(|line| !self.http_only || line.contains("http: GET "))
+            .collect()
+    }
+}
+
+/// Shows the latest lines of the log as they're written, until the panel is closed.
+pub fn start_log_panel<B: Backend>(terminal: &mut Terminal<B>) -> Result<(), io::Error> {
+    let mut panel = LogPanel {
+        scroll: 0,
+        http_only: false,
+    };
+
+    loop {
+        terminal.draw(|frame| draw_log_panel(frame, &mut panel))?;
+
+        if !poll(REFRESH_RATE)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if is_interrupt(&key) {
+                interrupt();
+            }
+
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('L') => return Ok(()),
+                KeyCode::Up => panel.scroll += 1,
+                KeyCode::Down => panel.scroll = panel.scroll.saturating_sub(1),
+                KeyCode::PageUp => panel.scroll += 10,
+                KeyCode::PageDown => panel.scroll = panel.scroll.saturating_sub(10),
+                KeyCode::End => panel.scroll = 0,
+                KeyCode::Char('h') => {
+                    panel.http_only = !panel.http_only;
+                    panel.scroll = 0;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn line_style(line: &str) -> Style {
+    if line.contains("[ERROR]") {
+        theme().error
+    } else if line.contains("[WARN]") {
+        theme().warning
+    } else {
+        Style::default()
+    }
+}
+
+fn draw_log_panel<B: Backend>(frame: &mut Frame<B>, panel: &mut LogPanel) {
+    if draw_too_small(frame) {
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(frame.size());
+
+    frame.render_widget(
+        Paragraph::new(Text::from(Spans::from(Span::raw(
+            "Go Back(Esc) Scroll(↑/↓/PgUp/PgDn) Follow(End) Only HTTP requests(h)",
+        )))),
+        chunks[0],
+    );
+
+    let lines = panel.lines();
+    let height = chunks[1].height.saturating_sub(2) as usize;
+    panel.scroll = panel.scroll.min(lines.len().saturating_sub(height));
+
+    let end = lines.len() - panel.scroll;
+    let shown: Vec<Spans> = lines[end.saturating_sub(height)..end]
+        .iter()
+        .map(|line| Spans::from(Span::styled(line.clone(), line_style(line))))
+        .collect();
+
+    let title = match (panel.scroll, panel.http_only) {
+        (0, false) => "Log - following".to_owned(),
+        (0, true) => "Log - HTTP requests, following".to_owned(),
+        (scroll, _) => format!("Log - {} line(s) up, End to follow", scroll),
+    };
+
+    frame.render_widget(
+        Paragraph::new(shown).block(Block::default().borders(Borders::ALL).title(title)),
+        chunks[1],
+    );
+}
@@ -1,27 +1,87 @@
-use std::io;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    error::Error,
+    io,
+    time::{Duration, Instant},
+};
 
-use common::{api::beatsaver::fetch_maps, types::map::Map};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use chrono::Utc;
+use common::{
+    api::{
+        beatsaver::{fetch_map_by_hash, Feed, SearchQuery},
+        check_connection,
+        download::download_map,
+        is_offline,
+        paginator::{PageSource, Paginator},
+    },
+    filter::{Filter, FilterError, FuzzyMatch},
+    sort::{sort_maps, Sort, SortDirection, SortKey},
+    store::{
+        download_queue::DownloadQueue,
+        downloads_dir,
+        export::export_maps,
+        favourites::Favourites,
+        history::History,
+        playlist::Playlist,
+        search_local,
+        searches::{SavedSearch, Searches},
+    },
+    types::map::Map,
+};
+use crossterm::event::{
+    self, poll, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
 
 use tui::{
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Span, Spans, Text},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{Block, Borders, Paragraph, TableState, Tabs},
     Frame, Terminal,
 };
 use unicode_width::UnicodeWidthStr;
 
-use crate::utils::loading::Loading;
+use crate::{
+    config::Config,
+    table_layout::TableLayout,
+    theme::theme,
+    utils::{
+        loading::Loading,
+        mouse::{contains, hint_key, tab_at, ClickTracker, TableRegion},
+        open::open_url,
+        preview_player::PreviewQueue,
+        terminal::is_interrupt,
+        text::ellipsize,
+    },
+};
 
-use super::map_detail;
+use super::{
+    command_palette::{draw_palette, Command, CommandPalette},
+    help::{self, draw_help},
+    layout::draw_too_small,
+    log_panel::start_log_panel,
+    map_detail::{draw_map_summary, DetailAction, DetailView},
+    map_table::{
+        column_widths, display_maps, fit_columns, page_status, sort_key_for, sort_label, Column,
+        ColumnLayout, SortKeyColumn,
+    },
+    messages::start_messages,
+    notifications::{dismiss_all, draw_toasts, notify, notify_error, Severity},
+    saved_searches::{check_new_maps, start_saved_searches},
+};
 
 #[derive(PartialEq)]
 enum InputMode {
     Normal,
     Editing,
     Sorting(SortMode),
+    Columns,
+    /// Typing the name to save the current search under.
+    Naming,
+    /// Picking a command from the command palette.
+    Command,
 }
 
 #[derive(PartialEq)]
@@ -30,32 +90,140 @@ enum SortMode {
     Filtering,
 }
 
-pub struct Browser {
+/// Where the maps of a browser tab come from.
+#[derive(PartialEq, Clone, Copy)]
+enum ResultSource {
+    Search,
+    Feed(Feed),
+    History,
+    Favourites,
+}
+
+impl ResultSource {
+    /// Every tab of the browser, in the order they're shown.
+    const TABS: [ResultSource; 7] = [
+        ResultSource::Search,
+        ResultSource::Feed(Feed::Latest),
+        ResultSource::Feed(Feed::Curated),
+        ResultSource::Feed(Feed::TopRated),
+        ResultSource::Feed(Feed::MostPlayed),
+        ResultSource::History,
+        ResultSource::Favourites,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            ResultSource::Search => "Search",
+            ResultSource::Feed(feed) => feed.name(),
+            ResultSource::History => "History",
+            ResultSource::Favourites => "Favourites",
+        }
+    }
+
+    fn is_local(&self) -> bool {
+        matches!(self, ResultSource::History | ResultSource::Favourites)
+    }
+
+    /// The BeatSaver listing behind the tab, `None` for tabs showing local maps.
+    fn page_source(&self, query: &str) -> Option<PageSource> {
+        match self {
+            ResultSource::Search => Some(PageSource::Search(SearchQuery::parse(query))),
+            ResultSource::Feed(feed) => Some(PageSource::Feed(*feed)),
+            ResultSource::History | ResultSource::Favourites => None,
+        }
+    }
+}
+
+/// Search box input that names one specific map rather than being a search query.
+#[derive(Debug, PartialEq)]
+enum MapLookup {
+    Key(String),
+    Hash(String),
+}
+
+impl MapLookup {
+    /// Recognises `!bsr <key>` and bare 40 character map hashes.
+    fn parse(input: &str) -> Option<MapLookup> {
+        let input = input.trim();
+
+        if let Some(key) = input.strip_prefix("!bsr") {
+            let key = key.trim();
+            if !key.is_empty() && key.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Some(MapLookup::Key(key.to_lowercase()));
+            }
+            return None;
+        }
+
+        if input.len() == 40 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Some(MapLookup::Hash(input.to_lowercase()));
+        }
+        None
+    }
+}
+
+/// How many rows before the end of a list the next page is fetched.
+const PREFETCH_DISTANCE: usize = 5;
+
+/// Terminals at least this wide show the selected map's details next to the list.
+const SPLIT_MIN_WIDTH: u16 = 160;
+
+/// How long to wait for input before redrawing, so playing previews are noticed ending.
+const TICK_RATE: Duration = Duration::from_millis(256);
+
+/// How often BeatSaver is checked while downloads are queued and it can't be reached.
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The maps shown in one browser tab.
+struct MapList {
+    source: ResultSource,
     query: String,
-    input: String,
     results: Vec<Map>,
     filtered_results: Vec<Map>,
-    input_mode: InputMode,
     table_state: TableState,
-    page_index: i32,
+    /// Set once the first page was requested, `None` for local tabs.
+    paginator: Option<Paginator>,
+    loaded: bool,
+    offline: bool,
+    /// Filter expression the maps are currently filtered by.
+    filter: String,
+    /// How the shown maps matched the bare words of the filter, by map id.
+    matches: HashMap<String, FuzzyMatch>,
+    region: TableRegion,
+    /// Ids of the maps marked for batch actions.
+    marked: HashSet<String>,
+    /// Row the range being marked started at, while marking a range.
+    range_start: Option<usize>,
 }
 
-impl Browser {
-    fn default() -> Browser {
-        Browser {
+impl MapList {
+    fn new(source: ResultSource) -> MapList {
+        MapList {
+            source,
             query: String::new(),
-            input: String::new(),
             results: Vec::new(),
             filtered_results: Vec::new(),
-            input_mode: InputMode::Normal,
             table_state: TableState::default(),
-            page_index: 1,
+            paginator: None,
+            loaded: false,
+            offline: false,
+            filter: String::new(),
+            matches: HashMap::new(),
+            region: TableRegion::default(),
+            marked: HashSet::new(),
+            range_start: None,
         }
     }
 
     fn set_results(&mut self, new_results: Vec<Map>) {
         self.results = new_results.clone();
         self.filtered_results = new_results;
+        self.matches.clear();
+    }
+
+    fn reset_filter(&mut self) {
+        self.filtered_results = self.results.clone();
+        self.filter.clear();
+        self.matches.clear();
     }
 
     fn append_results(&mut self, new_results: &mut Vec<Map>) {
@@ -64,7 +232,188 @@ impl Browser {
         self.filtered_results.append(&mut data);
     }
 
+    fn clear(&mut self) {
+        self.set_results(Vec::new());
+        self.table_state.select(None);
+        self.paginator = None;
+        self.loaded = false;
+        self.marked.clear();
+        self.range_start = None;
+    }
+
+    fn selected_map(&self) -> Option<&Map> {
+        self.filtered_results
+            .get(self.table_state.selected().unwrap_or(0))
+    }
+
+    fn title(&self) -> String {
+        let mut title = self.source.title().to_owned();
+
+        if let Some(paginator) = &self.paginator {
+            title = format!("{} - {}", title, page_status(paginator));
+        }
+        match self.marked_ids().len() {
+            0 => {}
+            count => title = format!("{} - {} marked", title, count),
+        }
+        if self.offline && !self.source.is_local() {
+            title.push_str(" - OFFLINE, showing cached and local results");
+        }
+        title
+    }
+
+    /// The rows between the start of the range being marked and the selection.
+    fn range(&self) -> Option<(usize, usize)> {
+        let start = self.range_start?;
+        let selected = self.table_state.selected().unwrap_or(0);
+        let last = self.filtered_results.len().checked_sub(1)?;
+
+        Some((start.min(selected).min(last), start.max(selected).min(last)))
+    }
+
+    /// Ids of the marked maps, including the range that is still being marked.
+    fn marked_ids(&self) -> HashSet<String> {
+        let mut marked = self.marked.clone();
+
+        if let Some((first, last)) = self.range() {
+            marked.extend(
+                self.filtered_results[first..=last]
+                    .iter()
+                    .map(|map| map.id.clone()),
+            );
+        }
+        marked
+    }
+
+    fn toggle_mark(&mut self) {
+        let id = match self.selected_map() {
+            Some(map) => map.id.clone(),
+            None => return,
+        };
+
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+    }
+
+    /// Starts marking a range at the selection, or marks the range if one was started.
+    fn toggle_range(&mut self) {
+        if self.range_start.is_some() {
+            self.marked = self.marked_ids();
+            self.range_start = None;
+        } else if !self.filtered_results.is_empty() {
+            self.range_start = Some(self.table_state.selected().unwrap_or(0));
+        }
+    }
+
+    /// Marks every shown map, or unmarks them all if they all are marked already.
+    fn toggle_mark_all(&mut self) {
+        if self
+            .filtered_results
+            .iter()
+            .all(|map| self.marked.contains(&map.id))
+        {
+            for map in &self.filtered_results {
+                self.marked.remove(&map.id);
+            }
+        } else {
+            self.marked
+                .extend(self.filtered_results.iter().map(|map| map.id.clone()));
+        }
+    }
+
+    /// The maps batch actions apply to: the shown marked maps, or the selected one if there
+    /// are none.
+    fn targets(&self) -> Vec<Map> {
+        let marked = self.marked_ids();
+        let targets: Vec<Map> = self
+            .filtered_results
+            .iter()
+            .filter(|map| marked.contains(&map.id))
+            .cloned()
+            .collect();
+
+        if targets.is_empty() {
+            self.selected_map().cloned().into_iter().collect()
+        } else {
+            targets
+        }
+    }
+
+    /// Title of the list in the tab bar, searches show their query.
+    fn tab_title(&self) -> String {
+        match self.source {
+            ResultSource::Search if !self.query.is_empty() => {
+                format!("Search: {}", ellipsize(&self.query, 20))
+            }
+            source => source.title().to_owned(),
+        }
+    }
+
+    fn has_more(&self) -> bool {
+        self.paginator.as_ref().is_some_and(Paginator::has_more)
+    }
+
+    /// Whether the selection got close enough to the end of the maps to fetch the next page.
+    /// Filtered lists aren't extended, the new maps wouldn't show up in them anyway.
+    fn needs_more(&self) -> bool {
+        let selected = self.table_state.selected().unwrap_or(0);
+
+        self.has_more()
+            && self.filtered_results.len() == self.results.len()
+            && selected + PREFETCH_DISTANCE >= self.filtered_results.len()
+    }
+
+    fn reload_saved_maps(&mut self) -> Result<(), Box<dyn Error>> {
+        let maps = match self.source {
+            ResultSource::History => History::load()?.maps(),
+            ResultSource::Favourites => Favourites::load()?.maps(),
+            _ => return Ok(()),
+        };
+
+        self.set_results(maps);
+        self.loaded = true;
+
+        if let Some(selected) = self.table_state.selected() {
+            if selected >= self.filtered_results.len() {
+                self.table_state
+                    .select(self.filtered_results.len().checked_sub(1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the selected map from the history or favourites list being shown.
+    fn remove_selected(&mut self) -> Result<(), Box<dyn Error>> {
+        let map = match self.table_state.selected() {
+            Some(selected) if selected < self.filtered_results.len() => {
+                &self.filtered_results[selected]
+            }
+            _ => return Ok(()),
+        };
+
+        match self.source {
+            ResultSource::History => {
+                let mut history = History::load()?;
+                history.remove(&map.id);
+                history.save()?;
+            }
+            ResultSource::Favourites => {
+                let mut favourites = Favourites::load()?;
+                favourites.remove(&map.id);
+                favourites.save()?;
+            }
+            _ => return Ok(()),
+        }
+
+        self.reload_saved_maps()
+    }
+
     fn next_item(&mut self) {
+        if self.filtered_results.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i >= self.filtered_results.len() - 1 {
@@ -78,7 +427,11 @@ impl Browser {
         self.table_state.select(Some(i));
     }
 
-    pub fn previous_item(&mut self) {
+    fn previous_item(&mut self) {
+        if self.filtered_results.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -93,187 +446,1081 @@ impl Browser {
     }
 }
 
-fn error_component<B: Backend>(frame: &mut Frame<B>) -> Rect {
-    let rows = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(
-            [
-                Constraint::Percentage(25),
-                Constraint::Percentage(50),
-                Constraint::Percentage(25),
-            ]
-            .as_ref(),
-        )
-        .split(frame.size());
+pub struct Browser {
+    input: String,
+    input_mode: InputMode,
+    lists: Vec<MapList>,
+    active_list: usize,
+    layout: TableLayout,
+    /// Column being moved or resized when configuring the columns.
+    selected_column: usize,
+    filter_error: Option<FilterError>,
+    /// Past queries, most recent first, loaded when the search box is opened.
+    search_history: Vec<String>,
+    /// Position in `search_history` while cycling through it, `None` for a new query.
+    history_index: Option<usize>,
+    /// Maps published since the saved searches with notifications were last run.
+    new_maps: usize,
+    /// Maps waiting to be downloaded once BeatSaver can be reached.
+    queued_downloads: usize,
+    /// When BeatSaver was last checked for the queued downloads.
+    last_probe: Instant,
+    /// Text of the key hint bar as last drawn, for clicking its entries.
+    hints: String,
+    hints_area: Rect,
+    tabs_area: Rect,
+    clicks: ClickTracker,
+    palette: CommandPalette,
+    /// Whether the keys of the current mode are listed on top of the browser.
+    show_help: bool,
+    previews: Option<PreviewQueue>,
+    /// Maps opened in their own tab, shown after the lists.
+    details: Vec<DetailView>,
+    /// The details tab being shown instead of the active list, if any.
+    active_details: Option<usize>,
+    /// Whether wide terminals show the selected map's details next to the list.
+    split: bool,
+}
 
-    let columns = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage(25),
-                Constraint::Percentage(50),
-                Constraint::Percentage(25),
-            ]
-            .as_ref(),
-        )
-        .split(rows[1]);
+impl Browser {
+    fn new(config: &Config, layout: TableLayout) -> Browser {
+        let default_source = match config.default_feed {
+            Some(feed) => ResultSource::Feed(feed),
+            None => ResultSource::Search,
+        };
 
-    (Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(50)].as_ref())
-        .split(columns[1]))[1]
+        Browser {
+            input: String::new(),
+            input_mode: InputMode::Normal,
+            lists: ResultSource::TABS.into_iter().map(MapList::new).collect(),
+            active_list: ResultSource::TABS
+                .iter()
+                .position(|source| *source == default_source)
+                .unwrap_or(0),
+            layout,
+            selected_column: 0,
+            filter_error: None,
+            search_history: Vec::new(),
+            history_index: None,
+            new_maps: 0,
+            queued_downloads: DownloadQueue::load()
+                .map(|queue| queue.maps.len())
+                .unwrap_or(0),
+            last_probe: Instant::now(),
+            hints: String::new(),
+            hints_area: Rect::default(),
+            tabs_area: Rect::default(),
+            clicks: ClickTracker::default(),
+            palette: CommandPalette::new(Command::all()),
+            show_help: false,
+            previews: None,
+            details: Vec::new(),
+            active_details: None,
+            split: true,
+        }
+    }
+
+    fn tab_count(&self) -> usize {
+        self.lists.len() + self.details.len()
+    }
+
+    /// Index of the active tab, counting the lists first and then the details tabs.
+    fn active_tab(&self) -> usize {
+        match self.active_details {
+            Some(index) => self.lists.len() + index,
+            None => self.active_list,
+        }
+    }
+
+    fn tab_titles(&self) -> Vec<String> {
+        self.lists
+            .iter()
+            .map(MapList::tab_title)
+            .chain(
+                self.details
+                    .iter()
+                    .map(|view| format!("♪ {}", ellipsize(&view.map().metadata.song_name, 20))),
+            )
+            .collect()
+    }
+
+    /// Stops the previews that finished playing.
+    fn tick(&mut self) {
+        for view in &mut self.details {
+            view.tick();
+        }
+
+        if self
+            .previews
+            .as_ref()
+            .is_some_and(|previews| previews.remaining() == 0)
+        {
+            self.previews = None;
+        }
+    }
+
+    fn list(&self) -> &MapList {
+        &self.lists[self.active_list]
+    }
+
+    fn list_mut(&mut self) -> &mut MapList {
+        &mut self.lists[self.active_list]
+    }
+
+    /// Orders the maps of the active tab by the sort of the table layout. While filtering by
+    /// bare words the best matches come first and the sort only breaks ties.
+    fn sort_list(&mut self) {
+        let list = &mut self.lists[self.active_list];
+        sort_maps(&mut list.filtered_results, &self.layout.sort);
+
+        if !list.matches.is_empty() {
+            let matches = &list.matches;
+            list.filtered_results
+                .sort_by_key(|m| Reverse(matches.get(&m.id).map_or(0, |m| m.score)));
+        }
+    }
+
+    /// Adds `key` to the end of the sort stack, flips it if it's ascending and drops it otherwise.
+    fn cycle_sort(&mut self, key: SortKey) {
+        let sort = &mut self.layout.sort;
+
+        match sort.iter().position(|s| s.key == key) {
+            Some(i) if sort[i].direction == SortDirection::Ascending => {
+                sort[i].direction = SortDirection::Descending
+            }
+            Some(i) => {
+                sort.remove(i);
+            }
+            None => sort.push(Sort {
+                key,
+                direction: SortDirection::Ascending,
+            }),
+        }
+    }
+
+    fn toggle_column(&mut self, column: Column) {
+        let columns = &mut self.layout.columns;
+
+        if columns.iter().any(|layout| layout.column == column) {
+            columns.retain(|layout| layout.column != column);
+        } else {
+            columns.push(ColumnLayout::new(column));
+        }
+        self.selected_column = self.selected_column.min(columns.len().saturating_sub(1));
+    }
+
+    /// Swaps the selected column with its neighbour, `offset` being -1 for left or 1 for right.
+    fn move_column(&mut self, offset: isize) {
+        let columns = &mut self.layout.columns;
+        let target = self.selected_column as isize + offset;
+
+        if target < 0 || target as usize >= columns.len() {
+            return;
+        }
+
+        columns.swap(self.selected_column, target as usize);
+        self.selected_column = target as usize;
+    }
+
+    fn resize_column(&mut self, change: i16) {
+        if let Some(layout) = self.layout.columns.get_mut(self.selected_column) {
+            layout.width = layout.width.saturating_add_signed(change).clamp(1, 100);
+        }
+    }
+
+    /// Opens the search box with an empty query.
+    fn edit_search(&mut self) {
+        self.input_mode = InputMode::Editing;
+        self.input.clear();
+        self.history_index = None;
+        self.search_history = Searches::load()
+            .map(|searches| searches.history)
+            .unwrap_or_default();
+    }
+
+    /// Shows the previous (`older`) or next query of the search history in the search box.
+    fn cycle_history(&mut self, older: bool) {
+        let index = match (self.history_index, older) {
+            (None, true) => 0,
+            (Some(i), true) => i + 1,
+            (None, false) | (Some(0), false) => {
+                self.history_index = None;
+                self.input.clear();
+                return;
+            }
+            (Some(i), false) => i - 1,
+        };
+
+        if let Some(query) = self.search_history.get(index) {
+            self.input = query.clone();
+            self.history_index = Some(index);
+        }
+    }
+
+    fn save_layout(&self) {
+        if let Err(e) = self.layout.save() {
+            notify_error("Failed to save the table layout", e.as_ref());
+        }
+    }
+
+    fn list_index(&self, source: ResultSource) -> usize {
+        self.lists
+            .iter()
+            .position(|list| list.source == source)
+            .unwrap_or(0)
+    }
 }
 
-pub async fn start_browser<B: Backend>(terminal: &mut Terminal<B>) -> Result<(), io::Error> {
-    let mut browser = Browser::default();
+pub async fn start_browser<B: Backend>(
+    terminal: &mut Terminal<B>,
+    config: &Config,
+    layout: TableLayout,
+) -> Result<(), io::Error> {
+    let mut browser = Browser::new(config, layout);
+    let default_list = browser.active_list;
+    switch_list(terminal, &mut browser, default_list).await;
+
+    if let Ok(searches) = Searches::load() {
+        if searches.saved.iter().any(|search| search.notify) {
+            let mut spinner = Loading::new(terminal);
+            spinner.start();
+            browser.new_maps = check_new_maps(&searches).await.into_iter().flatten().sum();
+        }
+    }
+
     loop {
         terminal.draw(|frame| draw_browser(frame, &mut browser))?;
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                match browser.input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('s') => {
-                            browser.input_mode = InputMode::Editing;
-                            browser.input.clear();
-                        }
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('S') => {
-                            browser.input_mode = InputMode::Sorting(SortMode::Normal);
-                            browser.input.clear();
-                        }
-                        KeyCode::Char('F') => {
-                            if browser.filtered_results.is_empty() {
-                                continue;
-                            }
-
-                            let mut spinner = Loading::new(terminal);
-                            spinner.start();
-
-                            let request = fetch_maps(&browser.query, browser.page_index).await;
-
-                            match request {
-                                Ok(mut data) => {
-                                    browser.append_results(&mut data);
-                                    browser.page_index += 1;
-                                }
-                                Err(e) => show_error(terminal, e.to_string()),
-                            }
-                        }
-                        KeyCode::Char('c') => browser.set_results(Vec::new()),
-                        KeyCode::Down => browser.next_item(),
-                        KeyCode::Up => browser.previous_item(),
-                        KeyCode::Enter => {
-                            if browser.filtered_results.is_empty() {
-                                continue;
-                            }
-                            let selected = browser.table_state.selected().unwrap_or(0);
-                            match map_detail::start_details(
-                                terminal,
-                                &browser.filtered_results[selected].id,
-                            )
-                            .await
-                            {
-                                Ok(_) => {}
-                                Err(e) => show_error(terminal, e.to_string()),
-                            }
-                        }
-                        _ => {}
-                    },
+        browser.tick();
+        if browser.queued_downloads > 0 {
+            if !is_offline() {
+                download_queued(terminal, &mut browser).await;
+            } else if browser.last_probe.elapsed() >= PROBE_INTERVAL {
+                // checked in the background, the queue is downloaded once it clears the flag
+                browser.last_probe = Instant::now();
+                tokio::spawn(check_connection());
+            }
+        }
+        if !poll(TICK_RATE)? {
+            continue;
+        }
 
-                    InputMode::Editing => match key.code {
-                        KeyCode::Enter => {
-                            browser.query = browser.input.clone();
-                            browser.input_mode = InputMode::Normal;
-
-                            let mut spinner = Loading::new(terminal);
-                            spinner.start();
-
-                            let request = fetch_maps(&browser.query, 0).await;
-
-                            match request {
-                                Ok(results) => {
-                                    browser.set_results(results);
-                                    browser.page_index = 1;
-                                }
-                                Err(e) => show_error(terminal, e.to_string()),
-                            };
-                        }
-                        KeyCode::Char(c) => {
-                            browser.input.push(c);
-                        }
-                        KeyCode::Backspace => {
-                            browser.input.pop();
-                        }
-                        KeyCode::Esc => browser.input_mode = InputMode::Normal,
-                        _ => {}
-                    },
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Mouse(mouse) => match handle_mouse(terminal, &mut browser, mouse).await {
+                Some(key) => key,
+                None => continue,
+            },
+            _ => continue,
+        };
 
-                    InputMode::Sorting(SortMode::Normal) => match key.code {
-                        KeyCode::Esc => {
-                            browser.input_mode = InputMode::Normal;
-                            browser.filtered_results = browser.results.clone();
-                        }
-                        KeyCode::Char('I') => sort_results(&mut browser, "id"),
-                        KeyCode::Char('N') => sort_results(&mut browser, "song_name"),
-                        KeyCode::Char('A') => sort_results(&mut browser, "author"),
-                        KeyCode::Char('D') => sort_results(&mut browser, "date"),
-                        KeyCode::Char('f') => {
-                            browser.input_mode = InputMode::Sorting(SortMode::Filtering);
-                            browser.input.clear();
-                        }
-                        KeyCode::Down => browser.next_item(),
-                        KeyCode::Up => browser.previous_item(),
-                        KeyCode::Enter => {
-                            if browser.filtered_results.is_empty() {
-                                continue;
-                            }
-                            let selected = browser.table_state.selected().unwrap_or(0);
-                            match map_detail::start_details(
-                                terminal,
-                                &browser.filtered_results[selected].id,
-                            )
-                            .await
-                            {
-                                Ok(_) => {}
-                                Err(e) => show_error(terminal, e.to_string()),
-                            }
-                        }
-                        _ => {}
-                    },
-                    InputMode::Sorting(SortMode::Filtering) => match key.code {
-                        KeyCode::Esc => {
-                            browser.input_mode = InputMode::Sorting(SortMode::Normal);
-                            browser.filtered_results = browser.results.clone();
-                        }
-                        KeyCode::Char(c) => {
-                            browser.input.push(c);
-                            let input = browser.input.clone();
-                            filter_results(&mut browser, &input)
-                        }
-                        KeyCode::Backspace => {
-                            browser.input.pop();
-                            let input = browser.input.clone();
-                            filter_results(&mut browser, &input)
-                        }
-                        KeyCode::Enter => {
-                            browser.input_mode = InputMode::Sorting(SortMode::Normal);
-                        }
-
-                        _ => {}
-                    },
+        if is_interrupt(&key) {
+            return Ok(());
+        }
+
+        if browser.show_help {
+            browser.show_help = false;
+            continue;
+        }
+
+        if let Some(index) = browser.active_details {
+            match key.code {
+                KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('1'..='9') => {
+                    switch_tab_by_key(terminal, &mut browser, key.code).await
+                }
+                KeyCode::Char('X') => close_details(terminal, &mut browser, index).await,
+                _ => match browser.details[index].handle_key(terminal, key).await {
+                    DetailAction::None => {}
+                    DetailAction::Close => close_details(terminal, &mut browser, index).await,
+                    DetailAction::OpenMap(id) => open_details(terminal, &mut browser, &id).await,
+                },
+            }
+            continue;
+        }
+
+        match browser.input_mode {
+            InputMode::Normal => match key.code {
+                KeyCode::Char('s') => browser.edit_search(),
+                KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char('S') => {
+                    browser.input_mode = InputMode::Sorting(SortMode::Normal);
+                    browser.input.clear();
+                }
+                KeyCode::Char('F') => {
+                    if !browser.list().has_more() {
+                        continue;
+                    }
+
+                    fetch_page(terminal, &mut browser).await;
+                }
+                KeyCode::Char('c') => browser.list_mut().clear(),
+                KeyCode::Char('C') => browser.input_mode = InputMode::Columns,
+                KeyCode::Char('w') => {
+                    let list = browser.list();
+                    if list.source == ResultSource::Search && list.loaded {
+                        browser.input_mode = InputMode::Naming;
+                        browser.input.clear();
+                    }
+                }
+                KeyCode::Char('l') => open_saved_searches(terminal, &mut browser).await,
+                KeyCode::Char(' ') => browser.list_mut().toggle_mark(),
+                KeyCode::Char('v') => browser.list_mut().toggle_range(),
+                KeyCode::Char('a') => browser.list_mut().toggle_mark_all(),
+                KeyCode::Esc => {
+                    let list = browser.list_mut();
+                    if list.range_start.take().is_none() {
+                        list.marked.clear();
+                    }
+                }
+                KeyCode::Char('D') => download_targets(terminal, &mut browser).await,
+                KeyCode::Char('P') => add_targets_to_playlist(&browser),
+                KeyCode::Char('x') => {
+                    if let Some(previews) = browser.previews.take() {
+                        previews.stop();
+                    }
+                }
+                KeyCode::Char('?') => browser.show_help = true,
+                KeyCode::Char('z') => dismiss_all(),
+                KeyCode::Char('M') => open_messages(terminal),
+                KeyCode::Char('L') => open_log(terminal),
+                KeyCode::Char(':') => {
+                    browser.palette.reset();
+                    browser.input_mode = InputMode::Command;
+                }
+                KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('1'..='9') => {
+                    switch_tab_by_key(terminal, &mut browser, key.code).await
+                }
+                KeyCode::Char('t') => {
+                    browser.lists.push(MapList::new(ResultSource::Search));
+                    let index = browser.lists.len() - 1;
+                    switch_list(terminal, &mut browser, index).await;
+                    browser.edit_search();
+                }
+                // only the search tabs opened with `t` can be closed
+                KeyCode::Char('X') if browser.active_list >= ResultSource::TABS.len() => {
+                    let index = browser.active_list;
+                    browser.lists.remove(index);
+                    switch_list(terminal, &mut browser, index - 1).await;
+                }
+                KeyCode::Char('|') => browser.split = !browser.split,
+                KeyCode::Char('h') => {
+                    let index = browser.list_index(ResultSource::History);
+                    switch_list(terminal, &mut browser, index).await;
+                }
+                KeyCode::Char('f') => {
+                    let index = browser.list_index(ResultSource::Favourites);
+                    switch_list(terminal, &mut browser, index).await;
+                }
+                KeyCode::Char('d') => {
+                    if let Err(e) = browser.list_mut().remove_selected() {
+                        notify_error("Failed to remove the map", e.as_ref());
+                    }
+                    browser.sort_list();
+                }
+                KeyCode::Down => select_next(terminal, &mut browser).await,
+                KeyCode::Up => browser.list_mut().previous_item(),
+                KeyCode::Enter => open_selected(terminal, &mut browser).await,
+                _ => {}
+            },
+
+            InputMode::Editing => match key.code {
+                KeyCode::Enter => {
+                    browser.input_mode = InputMode::Normal;
+
+                    if let Some(lookup) = MapLookup::parse(&browser.input) {
+                        open_lookup(terminal, &mut browser, lookup).await;
+                        continue;
+                    }
+
+                    let query = browser.input.clone();
+                    search(terminal, &mut browser, query).await;
+                }
+                KeyCode::Char(c) => {
+                    browser.input.push(c);
+                }
+                KeyCode::Backspace => {
+                    browser.input.pop();
+                }
+                KeyCode::Up => browser.cycle_history(true),
+                KeyCode::Down => browser.cycle_history(false),
+                KeyCode::Esc => browser.input_mode = InputMode::Normal,
+                _ => {}
+            },
+
+            InputMode::Naming => match key.code {
+                KeyCode::Enter => {
+                    browser.input_mode = InputMode::Normal;
+                    let name = browser.input.trim().to_owned();
+                    browser.input.clear();
+
+                    if !name.is_empty() {
+                        save_search(&browser, name);
+                    }
+                }
+                KeyCode::Char(c) => browser.input.push(c),
+                KeyCode::Backspace => {
+                    browser.input.pop();
+                }
+                KeyCode::Esc => {
+                    browser.input_mode = InputMode::Normal;
+                    browser.input.clear();
+                }
+                _ => {}
+            },
+
+            InputMode::Sorting(SortMode::Normal) => match key.code {
+                KeyCode::Esc => {
+                    browser.input_mode = InputMode::Normal;
+                    browser.list_mut().reset_filter();
+                    browser.sort_list();
+                }
+                KeyCode::Backspace => {
+                    browser.layout.sort.clear();
+                    // filtering again brings back the order the maps were loaded in
+                    let input = browser.input.clone();
+                    browser.filter_error = filter_results(browser.list_mut(), &input).err();
+                    browser.sort_list();
+                    browser.save_layout();
+                }
+                KeyCode::Char('f') => {
+                    browser.input_mode = InputMode::Sorting(SortMode::Filtering);
+                    browser.input.clear();
+                }
+                KeyCode::Char('?') => browser.show_help = true,
+                KeyCode::Char(c) => {
+                    if let Some(key) = sort_key_for(c) {
+                        browser.cycle_sort(key);
+                        browser.sort_list();
+                        browser.save_layout();
+                    }
+                }
+                KeyCode::Down => select_next(terminal, &mut browser).await,
+                KeyCode::Up => browser.list_mut().previous_item(),
+                KeyCode::Enter => open_selected(terminal, &mut browser).await,
+                _ => {}
+            },
+            InputMode::Sorting(SortMode::Filtering) => match key.code {
+                KeyCode::Esc => {
+                    browser.input_mode = InputMode::Sorting(SortMode::Normal);
+                    browser.filter_error = None;
+                    browser.list_mut().reset_filter();
+                    browser.sort_list();
+                }
+                KeyCode::Char(c) => {
+                    browser.input.push(c);
+                    let input = browser.input.clone();
+                    browser.filter_error = filter_results(browser.list_mut(), &input).err();
+                    browser.sort_list();
+                }
+                KeyCode::Backspace => {
+                    browser.input.pop();
+                    let input = browser.input.clone();
+                    browser.filter_error = filter_results(browser.list_mut(), &input).err();
+                    browser.sort_list();
+                }
+                KeyCode::Enter => {
+                    browser.input_mode = InputMode::Sorting(SortMode::Normal);
+                }
+
+                _ => {}
+            },
+
+            InputMode::Columns => match key.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    browser.input_mode = InputMode::Normal;
+                    browser.save_layout();
+                }
+                KeyCode::Left => {
+                    browser.selected_column = browser.selected_column.saturating_sub(1)
+                }
+                KeyCode::Right if browser.selected_column + 1 < browser.layout.columns.len() => {
+                    browser.selected_column += 1
+                }
+                KeyCode::Char('<') => browser.move_column(-1),
+                KeyCode::Char('>') => browser.move_column(1),
+                KeyCode::Char('+') => browser.resize_column(2),
+                KeyCode::Char('-') => browser.resize_column(-2),
+                KeyCode::Char('?') => browser.show_help = true,
+                KeyCode::Char(c) => {
+                    if let Some(column) = Column::from_key(c) {
+                        browser.toggle_column(column);
+                    }
+                }
+                _ => {}
+            },
+
+            InputMode::Command => match key.code {
+                KeyCode::Esc => browser.input_mode = InputMode::Normal,
+                KeyCode::Enter => {
+                    browser.input_mode = InputMode::Normal;
+                    if let Some(command) = browser.palette.selected() {
+                        run_command(terminal, &mut browser, command).await;
+                    }
+                }
+                KeyCode::Char(c) => browser.palette.push(c),
+                KeyCode::Backspace => browser.palette.pop(),
+                KeyCode::Down => browser.palette.next_item(),
+                KeyCode::Up => browser.palette.previous_item(),
+                _ => {}
+            },
+        }
+    }
+}
+
+async fn run_command<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    command: Command,
+) {
+    match command {
+        Command::Search => browser.edit_search(),
+        Command::SortBy(key, direction) => {
+            browser.layout.sort = vec![Sort { key, direction }];
+            browser.sort_list();
+            browser.save_layout();
+        }
+        Command::Download => download_targets(terminal, browser).await,
+        Command::AddToPlaylist => add_targets_to_playlist(browser),
+        Command::Favourite => favourite_targets(browser),
+        Command::Export(format) => match export_maps(&browser.list().targets(), format) {
+            Ok(path) => notify(Severity::Info, format!("Exported to {}", path.display())),
+            Err(e) => notify_error("Failed to export the maps", e.as_ref()),
+        },
+        Command::QueuePreviews => queue_previews(terminal, browser).await,
+        Command::MarkAll => browser.list_mut().toggle_mark_all(),
+        Command::OpenInBrowser => {
+            if let Some(map) = browser.list().selected_map() {
+                let url = format!("https://beatsaver.com/maps/{}", map.id);
+                if let Err(e) = open_url(&url) {
+                    notify_error(&format!("Failed to open {}", url), &e);
                 }
             }
         }
+        Command::SavedSearches => open_saved_searches(terminal, browser).await,
+        Command::Columns => browser.input_mode = InputMode::Columns,
+        Command::Help => browser.show_help = true,
+        Command::Messages => open_messages(terminal),
+        Command::Log => open_log(terminal),
     }
 }
 
+/// Downloads the marked maps, or the selected one, and unmarks them.
+async fn download_targets<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    let maps = browser.list().targets();
+    if maps.is_empty() {
+        return;
+    }
+
+    browser.list_mut().marked.clear();
+    download_maps(terminal, browser, maps).await;
+}
+
+/// Downloads the maps queued while BeatSaver couldn't be reached.
+async fn download_queued<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    browser.queued_downloads = 0;
+
+    let maps = DownloadQueue::load().and_then(|mut queue| {
+        let maps = queue.take();
+        queue.save()?;
+        Ok(maps)
+    });
+    match maps {
+        Ok(maps) => download_maps(terminal, browser, maps).await,
+        Err(e) => notify_error("Failed to read the download queue", e.as_ref()),
+    }
+}
+
+/// Downloads the maps, queueing them while BeatSaver can't be reached.
+async fn download_maps<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    maps: Vec<Map>,
+) {
+    if maps.is_empty() {
+        return;
+    }
+
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+
+    let mut downloaded = 0;
+    let mut queued = Vec::new();
+    let mut errors = Vec::new();
+    for map in maps {
+        if is_offline() {
+            queued.push(map);
+            continue;
+        }
+
+        match download_map(&map).await {
+            Ok(_) => downloaded += 1,
+            Err(_) if is_offline() => queued.push(map),
+            Err(e) => errors.push(format!("{}: {}", map.id, e)),
+        }
+    }
+    spinner.stop();
+
+    if downloaded > 0 {
+        notify(
+            Severity::Info,
+            format!(
+                "Saved {} map(s) to {}",
+                downloaded,
+                downloads_dir().display()
+            ),
+        );
+    }
+
+    if !queued.is_empty() {
+        let saved = DownloadQueue::load().and_then(|mut queue| {
+            for map in &queued {
+                queue.push(map);
+            }
+            queue.save()?;
+            Ok(queue.maps.len())
+        });
+        match saved {
+            Ok(count) => {
+                browser.queued_downloads = count;
+                notify(
+                    Severity::Info,
+                    format!(
+                        "Offline, {} map(s) will be downloaded once BeatSaver can be reached",
+                        queued.len()
+                    ),
+                );
+            }
+            Err(e) => notify_error("Failed to queue the downloads", e.as_ref()),
+        }
+    }
+
+    if !errors.is_empty() {
+        // some of them made it or will
+        let severity = if downloaded > 0 || !queued.is_empty() {
+            Severity::Warning
+        } else {
+            Severity::Error
+        };
+        notify(
+            severity,
+            format!("Failed to download some maps: {}", errors.join(", ")),
+        );
+    }
+}
+
+fn add_targets_to_playlist(browser: &Browser) {
+    let maps = browser.list().targets();
+    if maps.is_empty() {
+        return;
+    }
+
+    let added = Playlist::load().and_then(|mut playlist| {
+        let added = maps.iter().filter(|map| playlist.add(map)).count();
+        playlist.save()?;
+        Ok(added)
+    });
+
+    match added {
+        Ok(added) if added == maps.len() => {}
+        Ok(added) => notify(
+            Severity::Info,
+            format!(
+                "Added {} map(s), the others already are in the playlist",
+                added
+            ),
+        ),
+        Err(e) => notify_error("Failed to update the playlist", e.as_ref()),
+    }
+}
+
+fn favourite_targets(browser: &mut Browser) {
+    let maps = browser.list().targets();
+
+    if let Err(e) = Favourites::load().and_then(|mut favourites| {
+        for map in &maps {
+            favourites.add(map);
+        }
+        favourites.save()
+    }) {
+        notify_error("Failed to update the favourites", e.as_ref());
+    }
+
+    if let Err(e) = browser.list_mut().reload_saved_maps() {
+        notify_error("Failed to reload the maps", e.as_ref());
+    }
+    browser.sort_list();
+}
+
+/// Plays the previews of the marked maps, or the selected one, one after another.
+async fn queue_previews<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    let previews: Vec<(String, String)> = browser
+        .list()
+        .targets()
+        .iter()
+        .filter_map(|map| Some((map.id.clone(), map.published_version()?.preview_url.clone())))
+        .collect();
+    if previews.is_empty() {
+        return;
+    }
+
+    if let Some(previews) = browser.previews.take() {
+        previews.stop();
+    }
+
+    let queue = match PreviewQueue::new() {
+        Ok(queue) => queue,
+        Err(e) => {
+            notify_error("Failed to play the previews", e.as_ref());
+            return;
+        }
+    };
+
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+    // one missing preview shouldn't keep the others from playing
+    for (id, url) in &previews {
+        if let Err(e) = queue.add(url).await {
+            notify_error(&format!("Skipped the preview of {}", id), e.as_ref());
+        }
+    }
+    spinner.stop();
+
+    if queue.remaining() > 0 {
+        browser.previews = Some(queue);
+    }
+}
+
+fn open_messages<B: Backend>(terminal: &mut Terminal<B>) {
+    if let Err(e) = start_messages(terminal) {
+        notify_error("Failed to show the messages", &e);
+    }
+}
+
+fn open_log<B: Backend>(terminal: &mut Terminal<B>) {
+    if let Err(e) = start_log_panel(terminal) {
+        notify_error("Failed to show the log", &e);
+    }
+}
+
+async fn open_saved_searches<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    browser.new_maps = 0;
+
+    match start_saved_searches(terminal).await {
+        Ok(Some(saved)) => run_saved_search(terminal, browser, saved).await,
+        Ok(None) => {}
+        Err(e) => notify_error("Failed to open the saved searches", e.as_ref()),
+    }
+}
+
+/// Turns a mouse event into the key press it stands for. Clicking a row selects it and
+/// double clicking opens it, clicking a tab or a key hint presses its key and the wheel scrolls.
+async fn handle_mouse<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    mouse: MouseEvent,
+) -> Option<KeyEvent> {
+    let (column, row) = (mouse.column, mouse.row);
+
+    if let Some(index) = browser.active_details {
+        if !contains(browser.tabs_area, column, row) {
+            return browser.details[index].handle_mouse(mouse);
+        }
+    }
+
+    match mouse.kind {
+        // the arrows cycle through the search history while editing
+        MouseEventKind::ScrollDown if browser.input_mode != InputMode::Editing => {
+            Some(KeyEvent::from(KeyCode::Down))
+        }
+        MouseEventKind::ScrollUp if browser.input_mode != InputMode::Editing => {
+            Some(KeyEvent::from(KeyCode::Up))
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if contains(browser.hints_area, column, row) {
+                return hint_key(&browser.hints, column - browser.hints_area.x);
+            }
+
+            if contains(browser.tabs_area, column, row) {
+                if browser.active_details.is_none() && browser.input_mode != InputMode::Normal {
+                    return None;
+                }
+
+                let titles = browser.tab_titles();
+                let titles: Vec<&str> = titles.iter().map(String::as_str).collect();
+                let index = tab_at(&titles, browser.tabs_area, column)?;
+                return char::from_digit(index as u32 + 1, 10)
+                    .map(|c| KeyEvent::from(KeyCode::Char(c)));
+            }
+
+            if !matches!(
+                browser.input_mode,
+                InputMode::Normal | InputMode::Sorting(_)
+            ) {
+                return None;
+            }
+
+            let index = browser.list().region.row_at(column, row)?;
+            browser.list_mut().table_state.select(Some(index));
+
+            if browser.clicks.click(row) {
+                return Some(KeyEvent::from(KeyCode::Enter));
+            }
+
+            if browser.list().needs_more() {
+                fetch_page(terminal, browser).await;
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Switches to the next or previous tab for Tab and Shift+Tab, or the numbered one for 1-9.
+async fn switch_tab_by_key<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    code: KeyCode,
+) {
+    let count = browser.tab_count();
+    let index = match code {
+        KeyCode::Tab => (browser.active_tab() + 1) % count,
+        KeyCode::BackTab => (browser.active_tab() + count - 1) % count,
+        KeyCode::Char(c @ '1'..='9') => c as usize - '1' as usize,
+        _ => return,
+    };
+
+    if index >= count {
+        return;
+    }
+
+    if index < browser.lists.len() {
+        switch_list(terminal, browser, index).await;
+    } else {
+        browser.active_details = Some(index - browser.lists.len());
+    }
+}
+
+/// Makes the list at `index` the active tab, loading its maps if it hasn't been shown yet.
+async fn switch_list<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser, index: usize) {
+    browser.active_list = index;
+    browser.active_details = None;
+
+    match browser.list().source {
+        ResultSource::History | ResultSource::Favourites => {
+            if let Err(e) = browser.list_mut().reload_saved_maps() {
+                notify_error("Failed to reload the maps", e.as_ref());
+            }
+        }
+        ResultSource::Feed(_) if !browser.list().loaded => fetch_page(terminal, browser).await,
+        _ => {}
+    }
+    browser.sort_list();
+}
+
+/// Fetches the next page of the active tab and appends it to its results.
+async fn fetch_page<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    let list = browser.list_mut();
+
+    let paginator = match &mut list.paginator {
+        Some(paginator) => paginator,
+        None => match list.source.page_source(&list.query) {
+            Some(source) => list.paginator.insert(Paginator::new(source)),
+            None => return,
+        },
+    };
+
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+
+    match paginator.fetch_next().await {
+        Ok(mut data) => {
+            list.append_results(&mut data);
+            list.loaded = true;
+            list.offline = is_offline();
+        }
+        Err(_) if is_offline() && list.source == ResultSource::Search && !list.loaded => {
+            list.set_results(search_local(&SearchQuery::parse(&list.query)));
+            list.paginator = None;
+            list.loaded = true;
+            list.offline = true;
+        }
+        Err(e) => notify_error("Failed to fetch the maps", e.as_ref()),
+    }
+    browser.sort_list();
+}
+
+/// Moves the selection down, fetching the next page when it gets close to the end.
+async fn select_next<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    browser.list_mut().next_item();
+
+    if browser.list().needs_more() {
+        fetch_page(terminal, browser).await;
+    }
+}
+
+/// Searches in the active tab if it's a search tab, in the first one otherwise.
+async fn search<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser, query: String) {
+    browser.active_details = None;
+    if browser.list().source != ResultSource::Search {
+        browser.active_list = browser.list_index(ResultSource::Search);
+    }
+
+    if let Err(e) = Searches::load().and_then(|mut searches| {
+        searches.record(&query);
+        searches.save()
+    }) {
+        notify_error("Failed to save the search history", e.as_ref());
+    }
+
+    let list = browser.list_mut();
+    list.clear();
+    list.query = query;
+
+    fetch_page(terminal, browser).await;
+}
+
+/// Saves the active search tab's query along with its filter and the table's sort under `name`.
+fn save_search(browser: &Browser, name: String) {
+    let list = browser.list();
+    let search = SavedSearch {
+        name,
+        query: list.query.clone(),
+        filter: list.filter.clone(),
+        sort: browser.layout.sort.clone(),
+        notify: false,
+        last_run: Some(Utc::now()),
+    };
+
+    if let Err(e) = Searches::load().and_then(|mut searches| {
+        searches.add(search);
+        searches.save()
+    }) {
+        notify_error("Failed to save the search", e.as_ref());
+    }
+}
+
+/// Searches again with a saved search's query, then filters and sorts the results like it was.
+async fn run_saved_search<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    saved: SavedSearch,
+) {
+    browser.layout.sort = saved.sort.clone();
+    browser.save_layout();
+
+    search(terminal, browser, saved.query.clone()).await;
+
+    if !saved.filter.is_empty() {
+        browser.input_mode = InputMode::Sorting(SortMode::Normal);
+        browser.input = saved.filter.clone();
+        browser.filter_error = filter_results(browser.list_mut(), &saved.filter).err();
+        browser.sort_list();
+    }
+
+    if let Err(e) = Searches::load().and_then(|mut searches| {
+        if let Some(search) = searches.saved.iter_mut().find(|s| s.name == saved.name) {
+            search.last_run = Some(Utc::now());
+        }
+        searches.save()
+    }) {
+        notify_error("Failed to update the saved search", e.as_ref());
+    }
+}
+
+async fn open_selected<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser) {
+    let id = match browser.list().selected_map() {
+        Some(map) => map.id.clone(),
+        None => return,
+    };
+
+    open_details(terminal, browser, &id).await;
+}
+
+async fn open_lookup<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    lookup: MapLookup,
+) {
+    let id = match lookup {
+        MapLookup::Key(key) => key,
+        MapLookup::Hash(hash) => {
+            let mut spinner = Loading::new(terminal);
+            spinner.start();
+
+            match fetch_map_by_hash(&hash).await {
+                Ok(map) => map.id,
+                Err(e) => {
+                    notify_error("Failed to find the map", e.as_ref());
+                    return;
+                }
+            }
+        }
+    };
+
+    open_details(terminal, browser, &id).await;
+}
+
+/// Opens the map's details in a new tab, or switches to its tab if it's open already.
+async fn open_details<B: Backend>(terminal: &mut Terminal<B>, browser: &mut Browser, id: &String) {
+    if let Some(index) = browser.details.iter().position(|view| view.map().id == *id) {
+        browser.active_details = Some(index);
+        return;
+    }
+
+    match DetailView::load(terminal, id).await {
+        Ok(view) => {
+            browser.details.push(view);
+            browser.active_details = Some(browser.details.len() - 1);
+        }
+        Err(e) => notify_error("Failed to open the map", e.as_ref()),
+    }
+}
+
+/// Closes the details tab and goes back to the list that was shown before it.
+async fn close_details<B: Backend>(
+    terminal: &mut Terminal<B>,
+    browser: &mut Browser,
+    index: usize,
+) {
+    browser.details.remove(index);
+
+    // viewing a map reorders the history and may have (un)favourited it
+    let list = browser.active_list;
+    switch_list(terminal, browser, list).await;
+}
+
 fn draw_browser<B: Backend>(frame: &mut Frame<B>, browser: &mut Browser) {
+    if draw_too_small(frame) {
+        return;
+    }
+
+    if let Some(index) = browser.active_details {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Length(1),
+                    Constraint::Min(1),
+                ]
+                .as_ref(),
+            )
+            .split(frame.size());
+
+        browser.tabs_area = chunks[1];
+        draw_tabs(frame, browser, chunks[1]);
+        browser.details[index].draw(frame, chunks[0], chunks[2]);
+        draw_toasts(frame);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints(
             [
+                Constraint::Length(1),
                 Constraint::Length(1),
                 Constraint::Length(3),
                 Constraint::Min(1),
@@ -288,190 +1535,403 @@ fn draw_browser<B: Backend>(frame: &mut Frame<B>, browser: &mut Browser) {
                 Span::raw("Exit(q) "),
                 Span::raw("Search(s) "),
                 Span::raw("Sort(S) "),
-                Span::raw(if !browser.results.is_empty() {
+                Span::raw("Switch tab(Tab/1-9) "),
+                Span::raw("New search tab(t) "),
+                Span::raw(if browser.active_list >= ResultSource::TABS.len() {
+                    "Close tab(X) "
+                } else {
+                    ""
+                }),
+                Span::raw("Columns(C) "),
+                Span::raw(if browser.list().has_more() {
                     "Fetch more(F) "
                 } else {
                     ""
                 }),
-                Span::raw("Clear(c)"),
+                Span::raw("Clear(c) "),
+                Span::raw(if browser.list().source.is_local() {
+                    "Remove(d) "
+                } else {
+                    "History(h) Favourites(f) "
+                }),
+                Span::raw(
+                    if browser.list().source == ResultSource::Search && browser.list().loaded {
+                        "Save search(w) "
+                    } else {
+                        ""
+                    },
+                ),
+                Span::raw("Saved searches(l) "),
+                Span::raw("Mark(Space/v/a) "),
+                Span::raw(
+                    if browser
+                        .previews
+                        .as_ref()
+                        .is_some_and(|previews| previews.remaining() > 0)
+                    {
+                        "Stop previews(x) "
+                    } else {
+                        ""
+                    },
+                ),
+                Span::raw("Commands(:) "),
+                Span::raw("Messages(M) "),
+                Span::raw("Log(L) "),
+                Span::raw("Help(?)"),
+                Span::styled(
+                    match browser.new_maps {
+                        0 => String::new(),
+                        count => format!(" [{} new]", count),
+                    },
+                    theme().success,
+                ),
+                Span::styled(
+                    match browser.queued_downloads {
+                        0 => String::new(),
+                        count => format!(" [{} queued]", count),
+                    },
+                    theme().warning,
+                ),
             ]
         }
-        InputMode::Editing => vec![Span::raw("Go Back(Esc) "), Span::raw("Search(Enter)")],
+        InputMode::Naming => vec![Span::raw("Go Back(Esc) "), Span::raw("Save(Enter)")],
+        InputMode::Command => vec![
+            Span::raw("Go Back(Esc) "),
+            Span::raw("Run(Enter) "),
+            Span::raw("Select(↑/↓)"),
+        ],
+        InputMode::Editing => vec![
+            Span::raw("Go Back(Esc) "),
+            Span::raw("Search(Enter) "),
+            Span::raw("History(↑/↓) "),
+            Span::raw("| Filter tags with tag:<tag> -tag:<tag> "),
+            Span::raw("| Open a map directly with !bsr <key> or its hash"),
+        ],
         InputMode::Sorting(SortMode::Normal) => {
-            vec![
-                Span::raw("Go Back(Esc) Filter(frame) | "),
-                Span::styled("Sort by: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw("ID(I) Song name(N) Artist(A) Date(D)"),
-            ]
+            let mut spans = vec![
+                Span::raw("Go Back(Esc) Filter(f) Reset(Backspace) Help(?) | "),
+                Span::styled("Then by: ", Style::default().add_modifier(Modifier::BOLD)),
+            ];
+            spans.extend(
+                SortKey::ALL
+                    .iter()
+                    .map(|key| Span::raw(format!("{}({}) ", key.column().name(), key.key()))),
+            );
+            if !browser.layout.sort.is_empty() {
+                let labels: Vec<String> = browser.layout.sort.iter().map(sort_label).collect();
+                spans.push(Span::styled(
+                    format!("| {}", labels.join(", ")),
+                    theme().label,
+                ));
+            }
+            spans
         }
-        InputMode::Sorting(SortMode::Filtering) => {
-            vec![Span::raw("Go Back(Esc) "), Span::raw("Confirm(Enter)")]
+        InputMode::Sorting(SortMode::Filtering) => vec![
+            Span::raw("Go Back(Esc) "),
+            Span::raw("Confirm(Enter) "),
+            Span::raw("| e.g. mapper:name bpm>180 nps:5..8 ranked diff:ExpertPlus -tag:anime"),
+        ],
+        InputMode::Columns => {
+            let mut spans = vec![Span::raw("Done(Esc) ")];
+            if let Some(selected) = browser.layout.columns.get(browser.selected_column) {
+                spans.push(Span::styled(
+                    format!("{} ", selected.column.name()),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+                spans.push(Span::raw("Select(←/→) Move(</>) Width(+/-) "));
+            }
+            spans.push(Span::styled(
+                "| Toggle: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.extend(Column::ALL.iter().map(|column| {
+                let shown = browser
+                    .layout
+                    .columns
+                    .iter()
+                    .any(|layout| layout.column == *column);
+                let style = if shown {
+                    theme().label
+                } else {
+                    Style::default()
+                };
+                Span::styled(format!("{}({}) ", column.name(), column.key()), style)
+            }));
+            spans
         }
     };
 
+    browser.hints = top_text_bar
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    browser.hints_area = chunks[0];
+    browser.tabs_area = chunks[1];
+
     frame.render_widget(
         Paragraph::new(Text::from(Spans::from(top_text_bar))),
         chunks[0],
     );
 
+    draw_tabs(frame, browser, chunks[1]);
+
     let input = Paragraph::new(browser.input.as_ref())
         .style(match browser.input_mode {
-            InputMode::Sorting(SortMode::Filtering) => Style::default().fg(Color::Magenta),
-            InputMode::Editing => Style::default().fg(Color::Magenta),
+            InputMode::Sorting(SortMode::Filtering) => theme().input,
+            InputMode::Editing | InputMode::Naming => theme().input,
             _ => Style::default(),
         })
         .block(Block::default().borders(Borders::ALL).title(
-            if browser.input_mode == InputMode::Sorting(SortMode::Normal) {
-                "Filter"
-            } else {
-                "Search"
+            match (&browser.input_mode, &browser.filter_error) {
+                (InputMode::Sorting(_), Some(error)) => Spans::from(vec![
+                    Span::raw("Filter - "),
+                    Span::styled(error.to_string(), theme().error),
+                ]),
+                (InputMode::Sorting(_), None) => Spans::from("Filter"),
+                (InputMode::Naming, _) => Spans::from("Save search as"),
+                _ => Spans::from("Search"),
             },
         ));
 
-    frame.render_widget(input, chunks[1]);
+    frame.render_widget(input, chunks[2]);
 
     match browser.input_mode {
         InputMode::Sorting(SortMode::Filtering) => frame.set_cursor(
-            chunks[1].x + browser.input.width() as u16 + 1,
-            chunks[1].y + 1,
+            chunks[2].x + browser.input.width() as u16 + 1,
+            chunks[2].y + 1,
         ),
-        InputMode::Editing => frame.set_cursor(
-            chunks[1].x + browser.input.width() as u16 + 1,
-            chunks[1].y + 1,
+        InputMode::Editing | InputMode::Naming => frame.set_cursor(
+            chunks[2].x + browser.input.width() as u16 + 1,
+            chunks[2].y + 1,
         ),
         _ => {}
     }
 
+    // wide terminals show the selected map next to the list
+    let (table_area, summary_area) = if browser.split && chunks[3].width >= SPLIT_MIN_WIDTH {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[3]);
+        (halves[0], Some(halves[1]))
+    } else {
+        (chunks[3], None)
+    };
+
+    let columns = fit_columns(&browser.layout.columns, table_area.width);
+    let widths = column_widths(&columns, table_area.width);
+    let list = &mut browser.lists[browser.active_list];
     frame.render_stateful_widget(
-        display_maps(&browser.filtered_results),
-        chunks[2],
-        &mut browser.table_state,
+        display_maps(
+            &list.filtered_results,
+            &columns,
+            &widths,
+            &browser.layout.sort,
+            &list.matches,
+            &list.marked_ids(),
+            list.title(),
+        ),
+        table_area,
+        &mut list.table_state,
+    );
+    list.region.update(
+        table_area,
+        list.table_state.selected(),
+        list.filtered_results.len(),
     );
-}
 
-fn show_error<B: Backend>(terminal: &mut Terminal<B>, error: String) {
-    terminal.clear().unwrap();
-    let text = vec![
-        Spans::from(Span::raw(error)),
-        Spans::from(Span::styled(
-            "press any key to continue",
-            Style::default().add_modifier(Modifier::BOLD),
-        )),
-    ];
+    if let (Some(area), Some(map)) = (summary_area, list.selected_map()) {
+        draw_map_summary(frame, map, area);
+    }
 
-    let paragraph = Paragraph::new(Text::from(text))
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
+    if browser.input_mode == InputMode::Command {
+        draw_palette(frame, &mut browser.palette, chunks[3]);
+    }
 
-    terminal
-        .draw(|frame| {
-            let error_rect = error_component(frame);
-            frame.render_widget(paragraph, error_rect);
-        })
-        .unwrap();
-    loop {
-        if let Event::Key(key) = event::read().unwrap() {
-            if key.kind == KeyEventKind::Press {
-                break;
-            }
-        }
+    if browser.show_help {
+        draw_help(frame, "Keys", &help_entries(browser));
     }
+    draw_toasts(frame);
 }
 
-fn sort_results(browser: &mut Browser, criteria: &str) {
-    match criteria {
-        "id" => browser.filtered_results.sort_by(|a, b| a.id.cmp(&b.id)),
-        "song_name" => browser.filtered_results.sort_by(|a, b| {
-            a.metadata
-                .song_name
-                .to_ascii_lowercase()
-                .cmp(&b.metadata.song_name.to_ascii_lowercase())
-        }),
-        "author" => browser.filtered_results.sort_by(|a, b| {
-            a.metadata
-                .song_author_name
-                .to_ascii_lowercase()
-                .cmp(&b.metadata.song_author_name.to_ascii_lowercase())
-        }),
-        "date" => browser
-            .filtered_results
-            .sort_by(|a, b| a.last_published_at.cmp(&b.last_published_at)),
-        _ => {}
+fn draw_tabs<B: Backend>(frame: &mut Frame<B>, browser: &Browser, area: Rect) {
+    let tabs = Tabs::new(browser.tab_titles().into_iter().map(Spans::from).collect())
+        .select(browser.active_tab())
+        .highlight_style(theme().header);
+
+    frame.render_widget(tabs, area);
+}
+
+/// Every key of the browser's current mode and what it does.
+fn help_entries(browser: &Browser) -> Vec<(String, String)> {
+    match browser.input_mode {
+        InputMode::Normal => help::entries(&[
+            ("↑/↓", "Select a map"),
+            ("Enter", "Open the selected map"),
+            ("s", "Search"),
+            ("S", "Sort and filter the results"),
+            ("C", "Configure the columns"),
+            (
+                "Tab/Shift+Tab",
+                "Next/previous tab, maps open in tabs after the lists",
+            ),
+            ("1-9", "Switch to a tab"),
+            ("t", "Open another search tab"),
+            ("X", "Close the search or map tab"),
+            (
+                "|",
+                "Show the selected map next to the list on wide terminals",
+            ),
+            ("h", "History tab"),
+            ("f", "Favourites tab"),
+            ("F", "Fetch the next page"),
+            ("c", "Clear the tab"),
+            ("d", "Remove the map from the history or favourites"),
+            ("w", "Save the search"),
+            ("l", "Saved searches"),
+            ("Space", "Mark or unmark the map"),
+            ("v", "Start or finish marking a range"),
+            ("a", "Mark or unmark all shown maps"),
+            ("Esc", "Cancel the range or unmark all"),
+            ("D", "Download the marked maps"),
+            ("P", "Add the marked maps to the playlist"),
+            ("x", "Stop the queued previews"),
+            ("z", "Dismiss the notifications"),
+            ("M", "Messages, Enter shows the causes of an error"),
+            ("L", "Log, with every HTTP request"),
+            (
+                ":",
+                "Command palette, batch actions use the marked maps or the selected one",
+            ),
+            ("q", "Exit"),
+        ]),
+        InputMode::Editing => help::entries(&[
+            ("Enter", "Search"),
+            ("↑/↓", "Previous searches"),
+            ("Esc", "Go back"),
+            ("tag:<tag>", "Only maps with the tag"),
+            ("-tag:<tag>", "Only maps without the tag"),
+            ("!bsr <key>", "Open the map with the key"),
+            ("<hash>", "Open the map with the hash"),
+        ]),
+        InputMode::Naming => help::entries(&[("Enter", "Save"), ("Esc", "Go back")]),
+        InputMode::Sorting(SortMode::Normal) => {
+            let mut entries = help::entries(&[
+                ("↑/↓", "Select a map"),
+                ("Enter", "Open the selected map"),
+                ("f", "Filter"),
+                ("Backspace", "Reset the sort"),
+                ("Esc", "Go back"),
+            ]);
+            entries.extend(SortKey::ALL.iter().map(|key| {
+                (
+                    key.key().to_string(),
+                    format!(
+                        "Sort by {}, press again to flip or remove",
+                        key.column().name()
+                    ),
+                )
+            }));
+            entries
+        }
+        InputMode::Sorting(SortMode::Filtering) => help::entries(&[
+            ("Enter", "Confirm the filter"),
+            ("Esc", "Remove the filter"),
+            ("mapper:name", "Text fields: id, song, artist, mapper"),
+            (
+                "bpm>180",
+                "Numbers: bpm, duration, rating, up, down, plays, downloads, nps, njs, stars",
+            ),
+            ("nps:5..8", "Ranges of numbers"),
+            ("diff:ExpertPlus", "Difficulties and modes"),
+            ("tag:anime", "Tags"),
+            ("ranked", "Ranked, qualified or curated maps"),
+            ("-term", "Leave out matching maps"),
+            ("words", "Fuzzy match names and mappers"),
+        ]),
+        InputMode::Columns => {
+            let mut entries = help::entries(&[
+                ("←/→", "Select a column"),
+                ("</>", "Move the column"),
+                ("+/-", "Change the column's width"),
+                ("Esc/Enter", "Done"),
+            ]);
+            entries.extend(Column::ALL.iter().map(|column| {
+                (
+                    column.key().to_string(),
+                    format!("Show or hide {}", column.name()),
+                )
+            }));
+            entries
+        }
+        InputMode::Command => help::entries(&[
+            ("Enter", "Run the command"),
+            ("↑/↓", "Select a command"),
+            ("Esc", "Go back"),
+        ]),
     }
 }
 
-fn filter_results(browser: &mut Browser, filter: &str) {
-    let mut filtered_results = Vec::new();
+/// Keeps the maps matching the filter expression, see [`Filter`] for its syntax.
+/// The shown maps are left alone if the expression doesn't parse.
+fn filter_results(list: &mut MapList, filter: &str) -> Result<(), FilterError> {
+    let parsed = Filter::parse(filter)?;
 
-    let lines: Vec<(String, Map)> = browser
-        .results
-        .clone()
-        .into_iter()
-        .map(|m| {
-            (
-                format!(
-                    "{} {} {} {} {}",
-                    m.id,
-                    m.metadata.song_name,
-                    m.metadata.song_author_name,
-                    m.metadata.level_author_name,
-                    m.last_published_at
-                ),
-                m,
-            )
-        })
-        .collect();
+    list.filter = filter.to_owned();
+    list.filtered_results.clear();
+    list.matches.clear();
 
-    for (line, map) in lines {
-        if line.to_lowercase().contains(&filter.to_lowercase()) {
-            filtered_results.push(map);
+    for map in &list.results {
+        if let Some(fuzzy_match) = parsed.fuzzy_match(map) {
+            if parsed.is_fuzzy() {
+                list.matches.insert(map.id.clone(), fuzzy_match);
+            }
+            list.filtered_results.push(map.clone());
         }
     }
-    browser.filtered_results = filtered_results;
+    Ok(())
 }
 
-fn display_maps(maps: &[Map]) -> Table<'static> {
-    let header = Row::new(vec![
-        Cell::from("ID"),
-        Cell::from("SONG NAME"),
-        Cell::from("SONG AUTHOR"),
-        Cell::from("LEVEL AUTHOR"),
-        Cell::from("DATE"),
-    ])
-    .style(
-        Style::default()
-            .add_modifier(Modifier::BOLD)
-            .fg(Color::Cyan),
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let rows: Vec<Row> = maps
-        .iter()
-        .map(|m| {
-            Row::new(vec![
-                Cell::from(m.id.to_owned()),
-                Cell::from(m.metadata.song_name.to_owned()),
-                Cell::from(m.metadata.song_author_name.to_owned()),
-                Cell::from(m.metadata.level_author_name.to_owned()),
-                Cell::from(
-                    m.last_published_at
-                        .split('T')
-                        .next()
-                        .unwrap_or("")
-                        .to_owned(),
-                ),
-            ])
-        })
-        .collect();
+    const HASH: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn looks_up_bsr_keys() {
+        assert_eq!(
+            MapLookup::parse("!bsr 1a2b"),
+            Some(MapLookup::Key("1a2b".to_owned()))
+        );
+        assert_eq!(
+            MapLookup::parse("  !bsr   1A2B "),
+            Some(MapLookup::Key("1a2b".to_owned()))
+        );
+        assert_eq!(MapLookup::parse("!bsr"), None);
+        assert_eq!(MapLookup::parse("!bsr zzz"), None);
+    }
 
-    Table::new(rows)
-        .widths(&[
-            Constraint::Percentage(10),
-            Constraint::Percentage(30),
-            Constraint::Percentage(30),
-            Constraint::Percentage(20),
-            Constraint::Percentage(10),
-        ])
-        .header(header)
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
-        .block(Block::default().borders(Borders::ALL).title("Maps"))
+    #[test]
+    fn looks_up_hashes() {
+        assert_eq!(
+            MapLookup::parse(HASH),
+            Some(MapLookup::Hash(HASH.to_owned()))
+        );
+        assert_eq!(
+            MapLookup::parse(&HASH.to_uppercase()),
+            Some(MapLookup::Hash(HASH.to_owned()))
+        );
+        assert_eq!(MapLookup::parse(&HASH[..39]), None);
+        assert_eq!(MapLookup::parse(&format!("{}8", HASH)), None);
+        assert_eq!(MapLookup::parse(&format!("{}g", &HASH[..39])), None);
+    }
+
+    #[test]
+    fn searches_hex_like_text() {
+        assert_eq!(MapLookup::parse("1a2b"), None);
+        assert_eq!(MapLookup::parse("deadbeef"), None);
+        assert_eq!(MapLookup::parse("cafe babe"), None);
+        assert_eq!(MapLookup::parse("bsr 1a2b"), None);
+    }
 }
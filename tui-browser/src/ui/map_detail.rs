@@ -1,93 +1,268 @@
 use common::{
     api::{
         beatsaver::fetch_map_details,
+        is_offline,
         scoresaber::{fetch_leaderboard, fetch_leaderboard_info},
     },
+    store::{favourites::Favourites, history::History},
     types::{
-        map::{Map, MapDifficulty},
+        map::{DeclaredAi, Map, MapDifficulty, Version, VersionState},
         ss_leaderboard::{LeaderBoardInfo, Score},
     },
 };
-use crossterm::event::{self, poll, Event, KeyCode, KeyEventKind};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use log::error;
 
-use std::{error::Error, io, time::Duration};
+use std::error::Error;
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Modifier,
     text::{Span, Spans, Text},
     widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
     Frame, Terminal,
 };
 
-use crate::utils::{
-    loading::Loading,
-    preview_player::{Preview, PreviewState},
+use super::{
+    description::Description,
+    help::{self, draw_help},
+    log_panel::start_log_panel,
+    map_table::tag_style,
+    mapper_profile,
+    messages::start_messages,
+    notifications::{dismiss_all, notify, notify_error, Severity},
 };
 
+use crate::{
+    theme::theme,
+    utils::{
+        clipboard::copy_to_clipboard,
+        loading::Loading,
+        mouse::{contains, hint_key, ClickTracker, TableRegion},
+        open::open_url,
+        preview_player::{Preview, PreviewState},
+        text::{ellipsize, pad_left},
+    },
+};
+
+/// Below this width the leaderboard goes under the details instead of next to them.
+const SIDE_LEADERBOARD_MIN_WIDTH: u16 = 140;
+
+const DIFFICULTY_WIDTHS: [Constraint; 11] = [Constraint::Percentage(100 / 11); 11];
+
 #[derive(PartialEq)]
 pub enum MapDetailActiveWindow {
     Difficulties,
     Leaderboard,
+    Description,
+}
+
+/// The links waiting for a second key after `o` or `y`.
+#[derive(Clone, Copy, PartialEq)]
+enum LinkMenu {
+    Open,
+    Copy,
+}
+
+impl LinkMenu {
+    fn title(self) -> &'static str {
+        match self {
+            LinkMenu::Open => "Open",
+            LinkMenu::Copy => "Copy to the clipboard",
+        }
+    }
+
+    /// The key, name and value of every link of the menu, without a value if the map doesn't
+    /// have it.
+    fn links(
+        self,
+        map_detail: &MapDetail,
+        leaderboard: &SSLeaderboard,
+    ) -> Vec<(char, &'static str, Option<String>)> {
+        let map = &map_detail.map;
+        let version = map_detail.version();
+        let map_url = format!("https://beatsaver.com/maps/{}", map.id);
+
+        match self {
+            LinkMenu::Open => vec![
+                ('b', "BeatSaver page", Some(map_url)),
+                ('p', "Preview", Some(version.preview_url.clone())),
+                (
+                    'l',
+                    "ScoreSaber leaderboard",
+                    leaderboard
+                        .leaderboard_diffs
+                        .get(leaderboard.current_leaderboard_index)
+                        .filter(|diff| diff.id != 0)
+                        .map(|diff| format!("https://scoresaber.com/leaderboard/{}", diff.id)),
+                ),
+                (
+                    'u',
+                    "Profile of the selected player",
+                    leaderboard
+                        .table_state
+                        .selected()
+                        .and_then(|i| leaderboard.scores.get(i))
+                        .map(|score| {
+                            format!(
+                                "https://scoresaber.com/u/{}",
+                                score.leaderboard_player_info.id
+                            )
+                        }),
+                ),
+            ],
+            LinkMenu::Copy => vec![
+                ('k', "!bsr key", Some(format!("!bsr {}", map.id))),
+                ('h', "Hash", Some(version.hash.clone())),
+                ('u', "BeatSaver URL", Some(map_url)),
+                ('d', "Download URL", Some(version.download_url.clone())),
+            ],
+        }
+    }
+
+    fn help_entries(
+        self,
+        map_detail: &MapDetail,
+        leaderboard: &SSLeaderboard,
+    ) -> Vec<(String, String)> {
+        self.links(map_detail, leaderboard)
+            .into_iter()
+            .map(|(key, name, value)| {
+                let name = match value {
+                    Some(_) => name.to_owned(),
+                    None => format!("{} (none)", name),
+                };
+                (key.to_string(), name)
+            })
+            .collect()
+    }
+
+    /// Opens or copies the link of the key, doing nothing for keys not in the menu.
+    fn run(self, key: KeyCode, map_detail: &MapDetail, leaderboard: &SSLeaderboard) {
+        let link = self
+            .links(map_detail, leaderboard)
+            .into_iter()
+            .find(|(link_key, _, _)| key == KeyCode::Char(*link_key));
+
+        let (name, value) = match link {
+            Some((_, name, Some(value))) => (name, value),
+            Some((_, name, None)) => {
+                notify(Severity::Warning, format!("No {} to use", name));
+                return;
+            }
+            None => return,
+        };
+
+        match self {
+            LinkMenu::Open => {
+                if let Err(e) = open_url(&value) {
+                    notify_error(&format!("Failed to open {}", value), &e);
+                }
+            }
+            LinkMenu::Copy => match copy_to_clipboard(&value) {
+                Ok(()) => notify(Severity::Info, format!("Copied {}: {}", name, value)),
+                Err(e) => notify_error("Failed to copy to the clipboard", e.as_ref()),
+            },
+        }
+    }
 }
 
 struct MapDetail {
     description_height: u16,
-    description_expanded: bool,
+    description: Description,
+    description_area: Rect,
     scoreboard_shown: bool,
-    scoreboard_width: u16,
     map: Map,
     active_window: MapDetailActiveWindow,
+    favourite: bool,
+    offline: bool,
+    selected_version: usize,
+    /// Text of the key hint bar as last drawn, for clicking its entries.
+    hints: String,
+    hints_area: Rect,
+    clicks: ClickTracker,
+    /// Whether the keys are listed on top of the details.
+    show_help: bool,
+    link_menu: Option<LinkMenu>,
 }
 
 struct DifficultyTable {
     table_state: TableState,
     difficulties: Vec<MapDifficulty>,
+    region: TableRegion,
 }
 
 struct SSLeaderboard {
     table_state: TableState,
     scores: Vec<Score>,
     leaderboard_diffs: Vec<LeaderBoardInfo>,
+    region: TableRegion,
 
     current_leaderboard_index: usize,
 }
 
 impl MapDetail {
     async fn new(bsr: &String) -> Result<MapDetail, Box<dyn Error>> {
-        let response = fetch_map_details(bsr).await;
-
-        match response {
-            Ok(map) => Ok(MapDetail {
-                description_height: 50,
-                description_expanded: false,
-                scoreboard_shown: true,
-                scoreboard_width: 50,
-                map,
-                active_window: MapDetailActiveWindow::Difficulties,
-            }),
-            Err(_) => Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
-                "Failed to fetch map",
-            ))),
-        }
+        let map = fetch_map_details(bsr).await?;
+
+        Ok(MapDetail {
+            description_height: 50,
+            description: Description::parse(&map.description),
+            description_area: Rect::default(),
+            scoreboard_shown: true,
+            favourite: Favourites::load()
+                .map(|favourites| favourites.contains(&map.id))
+                .unwrap_or(false),
+            active_window: MapDetailActiveWindow::Difficulties,
+            offline: is_offline(),
+            // versions[0] may be an unpublished testplay, so prefer the published one
+            selected_version: map
+                .versions
+                .iter()
+                .position(|version| version.state == VersionState::Published)
+                .unwrap_or(0),
+            map,
+            hints: String::new(),
+            hints_area: Rect::default(),
+            clicks: ClickTracker::default(),
+            show_help: false,
+            link_menu: None,
+        })
     }
 
-    fn toggle_description(&mut self) {
-        if self.description_expanded {
+    /// Expands the description to scroll through it and pick its links, or goes back to the
+    /// table that was active before.
+    fn toggle_description(&mut self, leaderboard: &SSLeaderboard) {
+        if self.active_window == MapDetailActiveWindow::Description {
             self.description_height = 50;
+            self.active_window = if leaderboard.table_state.selected().is_some() {
+                MapDetailActiveWindow::Leaderboard
+            } else {
+                MapDetailActiveWindow::Difficulties
+            };
         } else {
             self.description_height = 100;
+            self.active_window = MapDetailActiveWindow::Description;
         }
-        self.description_expanded = !self.description_expanded;
+    }
+
+    fn version(&self) -> &Version {
+        &self.map.versions[self.selected_version]
+    }
+
+    fn toggle_favourite(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut favourites = Favourites::load()?;
+        self.favourite = favourites.toggle(&self.map);
+        favourites.save()
+    }
+
+    fn record_history(&self) -> Result<(), Box<dyn Error>> {
+        let mut history = History::load()?;
+        history.record(&self.map);
+        history.save()
     }
 
     fn toggle_scoreboard(&mut self) {
-        if self.scoreboard_shown {
-            self.scoreboard_width = 50;
-        } else {
-            self.scoreboard_width = 0;
-        }
         self.scoreboard_shown = !self.scoreboard_shown;
     }
 }
@@ -97,10 +272,15 @@ impl DifficultyTable {
         Self {
             table_state: TableState::default(),
             difficulties: diffs,
+            region: TableRegion::default(),
         }
     }
 
     fn next_item(&mut self) {
+        if self.difficulties.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i >= self.difficulties.len() - 1 {
@@ -115,6 +295,10 @@ impl DifficultyTable {
     }
 
     fn previous_item(&mut self) {
+        if self.difficulties.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -130,10 +314,14 @@ impl DifficultyTable {
 }
 
 impl SSLeaderboard {
-    async fn new(map: &Map) -> Result<Self, Box<dyn Error>> {
+    /// Fetches the leaderboard of every difficulty of the version. Versions that aren't on
+    /// ScoreSaber, like testplays and unranked or old hashes, get empty leaderboards and the
+    /// first failure is reported.
+    async fn new(version: &Version) -> Self {
         let mut leaderboard_diffs = Vec::new();
+        let mut failure = None;
 
-        for diff in &map.versions[0].diffs {
+        for diff in &version.diffs {
             if diff.characteristic == "Lightshow" {
                 leaderboard_diffs.push(LeaderBoardInfo::default());
                 continue;
@@ -141,43 +329,55 @@ impl SSLeaderboard {
 
             let diff_id = get_diff_id(&diff.difficulty);
 
-            let response = fetch_leaderboard_info(
-                &map.versions[0].hash,
+            let info = fetch_leaderboard_info(
+                &version.hash,
                 diff_id,
                 &("Solo".to_owned() + &diff.characteristic),
             )
-            .await;
+            .await
+            .unwrap_or_else(|e| {
+                failure.get_or_insert(e);
+                LeaderBoardInfo::default()
+            });
 
-            match response {
-                Ok(info) => {
-                    leaderboard_diffs.push(info);
-                }
-                Err(_) => {
-                    return Err(Box::new(io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Failed to fetch leaderboard {:?}", &diff.characteristic),
-                    )))
+            leaderboard_diffs.push(info);
+        }
+
+        let mut leaderboard = Self {
+            table_state: TableState::default(),
+            scores: Vec::new(),
+            leaderboard_diffs,
+            region: TableRegion::default(),
+            current_leaderboard_index: 0,
+        };
+        if !leaderboard.leaderboard_diffs.is_empty() {
+            match leaderboard.fetch_scores(0, 0).await {
+                Ok(scores) => leaderboard.scores = scores,
+                Err(e) => {
+                    failure.get_or_insert(e);
                 }
             }
         }
 
-        let response = fetch_leaderboard(leaderboard_diffs[0].id as u32, 0).await;
+        if let Some(e) = failure {
+            notify_error("Failed to fetch the ScoreSaber leaderboard", e.as_ref());
+        }
+        leaderboard
+    }
 
-        match response {
-            Ok(scores) => Ok(Self {
-                table_state: TableState::default(),
-                scores,
-                leaderboard_diffs,
-                current_leaderboard_index: 0,
-            }),
-            Err(_) => Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
-                "Failed to fetch scores".to_owned(),
-            ))),
+    /// A page of scores of a difficulty, none if it has no leaderboard.
+    async fn fetch_scores(&self, index: usize, page: u32) -> Result<Vec<Score>, Box<dyn Error>> {
+        match self.leaderboard_diffs[index].id {
+            0 => Ok(Vec::new()),
+            id => fetch_leaderboard(id as u32, page).await,
         }
     }
 
     fn next_item(&mut self) {
+        if self.scores.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i >= self.scores.len() - 1 {
@@ -192,6 +392,10 @@ impl SSLeaderboard {
     }
 
     fn previous_item(&mut self) {
+        if self.scores.is_empty() {
+            return;
+        }
+
         let i = match self.table_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -206,8 +410,7 @@ impl SSLeaderboard {
     }
 
     pub async fn get_scores_for_difficulty(&mut self, index: usize) {
-        let diff_id = self.leaderboard_diffs[index].id as u32;
-        let response = fetch_leaderboard(diff_id, 0).await;
+        let response = self.fetch_scores(index, 0).await;
 
         match response {
             Ok(scores) => {
@@ -220,8 +423,7 @@ impl SSLeaderboard {
     }
 
     pub async fn append_scores_for_difficulty(&mut self, index: usize, page: u32) {
-        let diff_id = self.leaderboard_diffs[index].id as u32;
-        let response = fetch_leaderboard(diff_id, page).await;
+        let response = self.fetch_scores(index, page).await;
 
         if let Ok(mut scores) = response {
             self.scores.append(&mut scores);
@@ -229,17 +431,6 @@ impl SSLeaderboard {
     }
 }
 
-fn get_difficulty_color(difficulty: &str) -> Color {
-    match difficulty {
-        "Easy" => Color::Green,
-        "Normal" => Color::Blue,
-        "Hard" => Color::Rgb(255, 99, 71),
-        "Expert" => Color::Red,
-        "ExpertPlus" => Color::Magenta,
-        _ => Color::White,
-    }
-}
-
 fn get_diff_id(diff: &str) -> u8 {
     match diff {
         "Easy" => 1,
@@ -263,143 +454,361 @@ fn get_diff_name(diff_id: u8) -> String {
     .to_owned()
 }
 
-pub fn truncate(s: &str, max_chars: usize) -> &str {
-    if max_chars == 0 {
-        return s;
+/// Formats a duration as `m:ss`.
+fn format_seconds(seconds: f32) -> String {
+    let seconds = seconds.round() as i32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn info_line(label: &str, value: &str, box_width: usize) -> Spans<'static> {
+    Spans::from(vec![
+        Span::styled(format!("{: <10} -> ", label), theme().label),
+        Span::raw(pad_left(&ellipsize(value, box_width), box_width)),
+    ])
+}
+
+/// Loads everything that depends on the map version: its preview and ScoreSaber leaderboards.
+/// The details work without either, so failing to fetch them is only reported.
+async fn load_version(version: &Version) -> (Preview, SSLeaderboard) {
+    let preview = Preview::new(&version.preview_url)
+        .await
+        .unwrap_or_else(|e| {
+            notify_error("Failed to fetch the preview", e.as_ref());
+            Preview::unavailable()
+        });
+    let leaderboard = SSLeaderboard::new(version).await;
+    (preview, leaderboard)
+}
+
+/// What the browser has to do after a details tab handled a key.
+pub enum DetailAction {
+    None,
+    Close,
+    /// Open another map, picked from the mapper's profile.
+    OpenMap(String),
+}
+
+/// A map's details with its difficulties, ScoreSaber leaderboard and preview, shown in a
+/// browser tab.
+pub struct DetailView {
+    map_detail: MapDetail,
+    difficulty_table: DifficultyTable,
+    leaderboard: SSLeaderboard,
+    preview: Preview,
+    /// Difficulty the leaderboard shows the scores of.
+    selected: usize,
+    current_leaderboard_page: u32,
+}
+
+impl DetailView {
+    /// Fetches the map and everything shown about it, and records it in the history.
+    pub async fn load<B: Backend>(
+        terminal: &mut Terminal<B>,
+        id: &String,
+    ) -> Result<DetailView, Box<dyn Error>> {
+        let mut spinner = Loading::new(terminal);
+
+        spinner.start();
+
+        let map_detail = MapDetail::new(id).await?;
+
+        let difficulty_table = DifficultyTable::new(map_detail.version().diffs.clone());
+
+        let (preview, leaderboard) = load_version(map_detail.version()).await;
+
+        spinner.stop();
+
+        if let Err(e) = map_detail.record_history() {
+            error!("Failed to record map history: {}", e);
+        }
+
+        Ok(DetailView {
+            map_detail,
+            difficulty_table,
+            leaderboard,
+            preview,
+            selected: 0,
+            current_leaderboard_page: 1,
+        })
     }
-    match s.char_indices().nth(max_chars) {
-        None => s,
-        Some((idx, _)) => &s[..idx],
+
+    pub fn map(&self) -> &Map {
+        &self.map_detail.map
     }
-}
 
-pub async fn start_details<B: Backend>(
-    terminal: &mut Terminal<B>,
-    id: &String,
-) -> Result<(), io::Error> {
-    let mut spinner = Loading::new(terminal);
+    /// Stops the preview once it played to the end, has to be called regularly.
+    pub fn tick(&mut self) {
+        if self.preview.finished() {
+            self.preview.stop();
+        }
+    }
 
-    spinner.start();
+    /// Handles a key press, returning what the browser has to do about it.
+    pub async fn handle_key<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        key: KeyEvent,
+    ) -> DetailAction {
+        let map_detail = &mut self.map_detail;
+        let difficulty_table = &mut self.difficulty_table;
+        let leaderboard = &mut self.leaderboard;
+        let preview = &mut self.preview;
+
+        if map_detail.show_help {
+            map_detail.show_help = false;
+            return DetailAction::None;
+        }
 
-    let mut map_detail = match MapDetail::new(id).await {
-        Ok(map_detail) => map_detail,
-        Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
-    };
+        if let Some(menu) = map_detail.link_menu.take() {
+            menu.run(key.code, map_detail, leaderboard);
+            return DetailAction::None;
+        }
 
-    let mut difficulty_table = DifficultyTable::new(map_detail.map.versions[0].diffs.clone());
+        match key.code {
+            KeyCode::Char('?') => {
+                map_detail.show_help = true;
+                return DetailAction::None;
+            }
+            KeyCode::Char('z') => {
+                dismiss_all();
+                return DetailAction::None;
+            }
+            KeyCode::Char('M') => {
+                if let Err(e) = start_messages(terminal) {
+                    notify_error("Failed to show the messages", &e);
+                }
+                return DetailAction::None;
+            }
+            KeyCode::Char('L') => {
+                if let Err(e) = start_log_panel(terminal) {
+                    notify_error("Failed to show the log", &e);
+                }
+                return DetailAction::None;
+            }
+            _ => {}
+        }
 
-    let mut preview = match Preview::new(&map_detail.map.versions[0].preview_url).await {
-        Ok(preview) => preview,
-        Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
-    };
+        match preview.state {
+            PreviewState::Paused => {
+                if let KeyCode::Char('r') = key.code {
+                    preview.resume();
+                }
+            }
 
-    let mut leaderboard = match SSLeaderboard::new(&map_detail.map).await {
-        Ok(leaderboard) => leaderboard,
-        Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
-    };
+            PreviewState::Playing => match key.code {
+                KeyCode::Char('s') => preview.stop(),
+                KeyCode::Char('P') => preview.pause(),
+                KeyCode::Char('i') => preview.inc_vol(),
+                KeyCode::Char('d') => preview.dec_vol(),
+                _ => {}
+            },
+            PreviewState::Stopped => match key.code {
+                KeyCode::Char('p') => {
+                    if let Err(e) = preview.play() {
+                        notify_error("Failed to play the preview", e.as_ref());
+                    }
+                }
+                KeyCode::Char('e') => map_detail.toggle_description(leaderboard),
+                KeyCode::Char('S') => map_detail.toggle_scoreboard(),
+                KeyCode::Char('o') => map_detail.link_menu = Some(LinkMenu::Open),
+                KeyCode::Char('y') => map_detail.link_menu = Some(LinkMenu::Copy),
+                KeyCode::Char('f') => {
+                    if let Err(e) = map_detail.toggle_favourite() {
+                        error!("Failed to update favourites: {}", e);
+                    }
+                }
+                KeyCode::Char(c @ ('v' | 'V')) => {
+                    let count = map_detail.map.versions.len();
+                    if count < 2 {
+                        return DetailAction::None;
+                    }
 
-    spinner.stop();
+                    let index = if c == 'v' {
+                        (map_detail.selected_version + 1) % count
+                    } else {
+                        (map_detail.selected_version + count - 1) % count
+                    };
+
+                    let mut spinner = Loading::new(terminal);
+                    spinner.start();
+
+                    let (new_preview, new_leaderboard) =
+                        load_version(&map_detail.map.versions[index]).await;
+                    spinner.stop();
+
+                    map_detail.selected_version = index;
+                    map_detail.active_window = MapDetailActiveWindow::Difficulties;
+                    map_detail.description_height = 50;
+                    *difficulty_table = DifficultyTable::new(map_detail.version().diffs.clone());
+                    *preview = new_preview;
+                    *leaderboard = new_leaderboard;
+                    self.selected = 0;
+                    self.current_leaderboard_page = 1;
+                }
+                KeyCode::Char('m') => {
+                    if map_detail.map.uploader.id == 0 {
+                        return DetailAction::None;
+                    }
 
-    let mut selected = 0;
-    let mut current_leaderboard_page = 1;
+                    match mapper_profile::start_mapper_profile(terminal, map_detail.map.uploader.id)
+                        .await
+                    {
+                        Ok(Some(id)) => return DetailAction::OpenMap(id),
+                        Ok(None) => {}
+                        Err(e) => notify_error("Failed to open the mapper's profile", e.as_ref()),
+                    }
+                }
+                KeyCode::Esc => {
+                    if map_detail.active_window != MapDetailActiveWindow::Description {
+                        return DetailAction::Close;
+                    }
+                    map_detail.toggle_description(leaderboard);
+                }
 
-    loop {
-        terminal.draw(|rect| {
-            draw_details(
-                rect,
-                &mut preview,
-                &mut map_detail,
-                &mut leaderboard,
-                &mut difficulty_table,
-            )
-        })?;
-
-        if poll(Duration::from_millis(256)).unwrap_or(false) {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match preview.state {
-                        PreviewState::Paused => {
-                            if let KeyCode::Char('r') = key.code {
-                                preview.resume();
+                KeyCode::Enter => {
+                    if map_detail.active_window == MapDetailActiveWindow::Description {
+                        // the first Enter shows which link the next one opens
+                        match map_detail.description.selected_url() {
+                            Some(url) => {
+                                if let Err(e) = open_url(url) {
+                                    notify_error(&format!("Failed to open {}", url), &e);
+                                }
                             }
+                            None => map_detail.description.next_link(),
                         }
+                        return DetailAction::None;
+                    }
+                    if map_detail.active_window == MapDetailActiveWindow::Leaderboard {
+                        return DetailAction::None;
+                    }
 
-                        PreviewState::Playing => match key.code {
-                            KeyCode::Char('s') => preview.stop(),
-                            KeyCode::Char('P') => preview.pause(),
-                            KeyCode::Char('i') => preview.inc_vol(),
-                            KeyCode::Char('d') => preview.dec_vol(),
-                            _ => {}
-                        },
-                        PreviewState::Stopped => match key.code {
-                            KeyCode::Char('p') => preview.play(),
-                            KeyCode::Char('e') => map_detail.toggle_description(),
-                            KeyCode::Char('S') => map_detail.toggle_scoreboard(),
-                            KeyCode::Esc => return Ok(()),
-
-                            KeyCode::Enter => {
-                                if map_detail.active_window == MapDetailActiveWindow::Leaderboard {
-                                    continue;
-                                }
+                    let mut spinner = Loading::new(terminal);
+                    spinner.start();
 
-                                let mut spinner = Loading::new(terminal);
-                                spinner.start();
+                    self.selected = difficulty_table.table_state.selected().unwrap_or(0);
+                    leaderboard.current_leaderboard_index = self.selected;
+                    leaderboard.get_scores_for_difficulty(self.selected).await;
+                    self.current_leaderboard_page = 1;
 
-                                selected = difficulty_table.table_state.selected().unwrap_or(0);
-                                leaderboard.current_leaderboard_index = selected;
-                                leaderboard.get_scores_for_difficulty(selected).await;
-                                current_leaderboard_page = 1;
+                    spinner.stop();
+                }
+                KeyCode::Char('F') => {
+                    self.current_leaderboard_page += 1;
+
+                    let mut spinner = Loading::new(terminal);
+                    spinner.start();
+                    leaderboard
+                        .append_scores_for_difficulty(self.selected, self.current_leaderboard_page)
+                        .await;
+                    spinner.stop();
+                }
 
-                                spinner.stop();
-                            }
-                            KeyCode::Char('F') => {
-                                current_leaderboard_page += 1;
-
-                                let mut spinner = Loading::new(terminal);
-                                spinner.start();
-                                leaderboard
-                                    .append_scores_for_difficulty(
-                                        selected,
-                                        current_leaderboard_page,
-                                    )
-                                    .await;
-                                spinner.stop();
-                            }
+                _ => match map_detail.active_window {
+                    MapDetailActiveWindow::Difficulties => match key.code {
+                        KeyCode::Up => difficulty_table.previous_item(),
+                        KeyCode::Down => difficulty_table.next_item(),
+                        KeyCode::Right => {
+                            map_detail.active_window = MapDetailActiveWindow::Leaderboard;
+                            difficulty_table.table_state.select(None);
+                            leaderboard.table_state.select(Some(0));
+                        }
+                        _ => {}
+                    },
+                    MapDetailActiveWindow::Leaderboard => match key.code {
+                        KeyCode::Up => leaderboard.previous_item(),
+                        KeyCode::Down => leaderboard.next_item(),
+                        KeyCode::Left => {
+                            map_detail.active_window = MapDetailActiveWindow::Difficulties;
+                            leaderboard.table_state.select(None);
+                            difficulty_table.table_state.select(Some(0));
+                        }
+
+                        _ => {}
+                    },
+                    MapDetailActiveWindow::Description => match key.code {
+                        KeyCode::Up => map_detail.description.scroll_up(1),
+                        KeyCode::Down => map_detail.description.scroll_down(1),
+                        KeyCode::PageUp => map_detail.description.scroll_up(10),
+                        KeyCode::PageDown => map_detail.description.scroll_down(10),
+                        KeyCode::Char('n') => map_detail.description.next_link(),
+                        KeyCode::Char('N') => map_detail.description.previous_link(),
+                        _ => {}
+                    },
+                },
+            },
+        }
+        DetailAction::None
+    }
 
-                            _ => match map_detail.active_window {
-                                MapDetailActiveWindow::Difficulties => match key.code {
-                                    KeyCode::Up => difficulty_table.previous_item(),
-                                    KeyCode::Down => difficulty_table.next_item(),
-                                    KeyCode::Right => {
-                                        map_detail.active_window =
-                                            MapDetailActiveWindow::Leaderboard;
-                                        difficulty_table.table_state.select(None);
-                                        leaderboard.table_state.select(Some(0));
-                                    }
-                                    _ => {}
-                                },
-                                MapDetailActiveWindow::Leaderboard => match key.code {
-                                    KeyCode::Up => leaderboard.previous_item(),
-                                    KeyCode::Down => leaderboard.next_item(),
-                                    KeyCode::Left => {
-                                        map_detail.active_window =
-                                            MapDetailActiveWindow::Difficulties;
-                                        leaderboard.table_state.select(None);
-                                        difficulty_table.table_state.select(Some(0));
-                                    }
-
-                                    _ => {}
-                                },
-                            },
-                        },
+    /// Turns a mouse event into the key press it stands for. Clicking a row selects it and
+    /// switches to its pane, clicking the description expands it, double clicking a difficulty
+    /// shows its scores and the wheel scrolls.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<KeyEvent> {
+        let map_detail = &mut self.map_detail;
+        let difficulty_table = &mut self.difficulty_table;
+        let leaderboard = &mut self.leaderboard;
+        let (column, row) = (mouse.column, mouse.row);
+
+        match mouse.kind {
+            MouseEventKind::ScrollDown => Some(KeyEvent::from(KeyCode::Down)),
+            MouseEventKind::ScrollUp => Some(KeyEvent::from(KeyCode::Up)),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if contains(map_detail.hints_area, column, row) {
+                    return hint_key(&map_detail.hints, column - map_detail.hints_area.x);
+                }
+
+                if contains(map_detail.description_area, column, row) {
+                    if map_detail.active_window != MapDetailActiveWindow::Description {
+                        map_detail.toggle_description(leaderboard);
+                    }
+                } else if contains(difficulty_table.region.area, column, row) {
+                    map_detail.description_height = 50;
+                    map_detail.active_window = MapDetailActiveWindow::Difficulties;
+                    leaderboard.table_state.select(None);
+
+                    match difficulty_table.region.row_at(column, row) {
+                        Some(index) => {
+                            difficulty_table.table_state.select(Some(index));
+                            if map_detail.clicks.click(row) {
+                                return Some(KeyEvent::from(KeyCode::Enter));
+                            }
+                        }
+                        None if difficulty_table.table_state.selected().is_none() => {
+                            difficulty_table.table_state.select(Some(0))
+                        }
+                        None => {}
+                    }
+                } else if contains(leaderboard.region.area, column, row) {
+                    map_detail.description_height = 50;
+                    map_detail.active_window = MapDetailActiveWindow::Leaderboard;
+                    difficulty_table.table_state.select(None);
+
+                    match leaderboard.region.row_at(column, row) {
+                        Some(index) => leaderboard.table_state.select(Some(index)),
+                        None if leaderboard.table_state.selected().is_none() => {
+                            leaderboard.table_state.select(Some(0))
+                        }
+                        None => {}
                     }
                 }
+                None
             }
-        }
-        if preview.sink.empty() {
-            preview.stop();
+            _ => None,
         }
     }
+
+    /// Draws the key hints into `hints_area` and the details into `area`.
+    pub fn draw<B: Backend>(&mut self, frame: &mut Frame<B>, hints_area: Rect, area: Rect) {
+        draw_details(
+            frame,
+            &mut self.preview,
+            &mut self.map_detail,
+            &mut self.leaderboard,
+            &mut self.difficulty_table,
+            hints_area,
+            area,
+        );
+    }
 }
 
 fn draw_details<B: Backend>(
@@ -408,50 +817,148 @@ fn draw_details<B: Backend>(
     map_detail: &mut MapDetail,
     leaderboard: &mut SSLeaderboard,
     difficulty_table: &mut DifficultyTable,
+    hints_area: Rect,
+    area: Rect,
 ) {
-    let columns = Layout::default()
-        .direction(Direction::Horizontal)
-        .margin(2)
-        .constraints(
-            [
-                Constraint::Min(1),
-                Constraint::Percentage(map_detail.scoreboard_width),
-            ]
-            .as_ref(),
-        )
-        .split(frame.size());
+    // narrow terminals get the leaderboard under the details
+    let (details_area, leaderboard_area) = if !map_detail.scoreboard_shown {
+        (area, None)
+    } else {
+        let (direction, leaderboard_size) = if area.width >= SIDE_LEADERBOARD_MIN_WIDTH {
+            (Direction::Horizontal, 50)
+        } else {
+            (Direction::Vertical, 40)
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Min(1), Constraint::Percentage(leaderboard_size)].as_ref())
+            .split(area);
+        (chunks[0], Some(chunks[1]))
+    };
 
     let left_boxes = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Length(1),
                 Constraint::Percentage(map_detail.description_height),
                 Constraint::Percentage(50),
             ]
             .as_ref(),
         )
-        .split(columns[0]);
-
-    let top_text = Spans::from(Span::raw(match preview.state {
-        PreviewState::Playing => "Pause(P) Stop(s) Increase Volume(i) Decrease Volume(d)",
-        PreviewState::Paused => "Resume(r)",
-        PreviewState::Stopped => {
-            "Close(Esc) Toggle Scoreboard(S) Play Preview(p) Fetch more scores(F)"
+        .split(details_area);
+
+    let top_text = Spans::from(match preview.state {
+        PreviewState::Playing => vec![Span::raw(
+            "Pause(P) Stop(s) Increase Volume(i) Decrease Volume(d) Help(?)",
+        )],
+        PreviewState::Paused => vec![Span::raw("Resume(r) Help(?)")],
+        PreviewState::Stopped if map_detail.active_window == MapDetailActiveWindow::Description => {
+            vec![Span::raw(if map_detail.description.has_links() {
+                "Back(Esc) Scroll(↑/↓/PgUp/PgDn) Next link(n) Previous link(N) Open link(Enter) \
+                 Help(?)"
+            } else {
+                "Back(Esc) Scroll(↑/↓/PgUp/PgDn) Help(?)"
+            })]
         }
-    }));
+        PreviewState::Stopped => vec![
+            Span::raw(
+                "Close(Esc) Toggle Scoreboard(S) Play Preview(p) Fetch more scores(F) Mapper(m) \
+                 Version(v/V) Description(e) Open(o) Copy(y) ",
+            ),
+            Span::raw(if map_detail.favourite {
+                "Unfavourite(f) "
+            } else {
+                "Favourite(f) "
+            }),
+            Span::raw("Messages(M) Log(L) Help(?)"),
+        ],
+    });
 
-    frame.render_widget(Paragraph::new(Text::from(top_text)), left_boxes[0]);
-    draw_leaderboard(frame, leaderboard, columns[1]);
+    map_detail.hints = top_text
+        .0
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    map_detail.hints_area = hints_area;
+
+    frame.render_widget(Paragraph::new(Text::from(top_text)), hints_area);
+    match leaderboard_area {
+        Some(leaderboard_area) => draw_leaderboard(frame, leaderboard, leaderboard_area),
+        // nothing to click on while it's hidden
+        None => leaderboard.region.update(Rect::default(), None, 0),
+    }
 
-    draw_top_left_box(frame, &map_detail.map, left_boxes[1]);
+    let focused = map_detail.active_window == MapDetailActiveWindow::Description;
+    map_detail.description_area = draw_top_left_box(
+        frame,
+        &map_detail.map,
+        map_detail.offline,
+        if focused {
+            "Description"
+        } else {
+            "Description (e to expand)"
+        },
+        &mut map_detail.description,
+        focused,
+        left_boxes[0],
+    );
     draw_bottom_left_box(
         frame,
         map_detail,
         difficulty_table,
         leaderboard,
-        left_boxes[2],
+        left_boxes[1],
     );
+
+    if map_detail.show_help {
+        draw_help(frame, "Keys", &help_entries(preview));
+    }
+    if let Some(menu) = map_detail.link_menu {
+        draw_help(
+            frame,
+            menu.title(),
+            &menu.help_entries(map_detail, leaderboard),
+        );
+    }
+}
+
+/// Every key available while the preview is in its current state and what it does.
+fn help_entries(preview: &Preview) -> Vec<(String, String)> {
+    match preview.state {
+        PreviewState::Playing => help::entries(&[
+            ("P", "Pause the preview"),
+            ("s", "Stop the preview"),
+            ("i", "Increase the volume"),
+            ("d", "Decrease the volume"),
+        ]),
+        PreviewState::Paused => help::entries(&[("r", "Resume the preview")]),
+        PreviewState::Stopped => help::entries(&[
+            ("↑/↓", "Select a difficulty or score"),
+            ("←/→", "Switch between difficulties and leaderboard"),
+            ("Enter", "Show the leaderboard of the difficulty"),
+            ("F", "Fetch more scores"),
+            ("p", "Play the preview"),
+            ("v/V", "Next/previous version"),
+            ("m", "Mapper profile"),
+            ("f", "Favourite or unfavourite the map"),
+            (
+                "o",
+                "Open the BeatSaver page, preview, leaderboard or a player's profile",
+            ),
+            ("y", "Copy the !bsr key, hash or a URL"),
+            (
+                "e",
+                "Expand the description to scroll it and open its links",
+            ),
+            ("n/N", "Select the next/previous link of the description"),
+            ("Enter", "Open the selected link of the description"),
+            ("S", "Show or hide the leaderboard"),
+            ("z", "Dismiss the notifications"),
+            ("M", "Messages"),
+            ("L", "Log"),
+            ("Esc", "Close"),
+        ]),
+    }
 }
 
 fn draw_leaderboard<B: Backend>(
@@ -460,7 +967,12 @@ fn draw_leaderboard<B: Backend>(
     right_column: Rect,
 ) {
     let scores = &ssleaderboard.scores;
-    let leaderboard = &ssleaderboard.leaderboard_diffs[ssleaderboard.current_leaderboard_index];
+    // a version without difficulties has no leaderboards at all
+    let no_leaderboard = LeaderBoardInfo::default();
+    let leaderboard = ssleaderboard
+        .leaderboard_diffs
+        .get(ssleaderboard.current_leaderboard_index)
+        .unwrap_or(&no_leaderboard);
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -475,18 +987,17 @@ fn draw_leaderboard<B: Backend>(
         Cell::from("SCORE"),
         Cell::from("MISSES"),
     ])
-    .style(
-        Style::default()
-            .add_modifier(Modifier::BOLD)
-            .fg(Color::Cyan),
-    );
+    .style(theme().header);
+
+    // whatever the other columns, their spacing and the borders leave
+    let name_width = (right_column.width as usize).saturating_sub(42);
 
     let rows: Vec<Row> = scores
         .iter()
         .map(|score| {
             Row::new(vec![
                 Cell::from(format!("{}", score.rank)),
-                Cell::from(score.leaderboard_player_info.name.to_owned()),
+                Cell::from(ellipsize(&score.leaderboard_player_info.name, name_width)),
                 Cell::from(format!(
                     "{:.2}%",
                     (score.base_score as f32 / leaderboard.max_score as f32) * 100.0
@@ -508,13 +1019,13 @@ fn draw_leaderboard<B: Backend>(
         .header(header)
         .widths(&[
             Constraint::Length(5),
-            Constraint::Length(40),
-            Constraint::Length(10),
-            Constraint::Length(10),
-            Constraint::Length(10),
-            Constraint::Length(20),
+            Constraint::Min(10),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(6),
         ])
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_style(theme().highlight)
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -526,6 +1037,11 @@ fn draw_leaderboard<B: Backend>(
         );
 
     frame.render_stateful_widget(table, chunks[0], &mut ssleaderboard.table_state);
+    ssleaderboard.region.update(
+        chunks[0],
+        ssleaderboard.table_state.selected(),
+        ssleaderboard.scores.len(),
+    );
 }
 
 fn draw_bottom_left_box<B: Backend>(
@@ -535,30 +1051,31 @@ fn draw_bottom_left_box<B: Backend>(
     leaderboard: &SSLeaderboard,
     bottom_box: Rect,
 ) {
-    let map = &map_detail.map;
+    let versions_height = map_detail.map.versions.len().min(4) as u16 + 2;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(4), Constraint::Min(1)].as_ref())
+        .constraints(
+            [
+                Constraint::Length(4),
+                Constraint::Length(versions_height),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
         .split(bottom_box);
 
     let links = vec![
         Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "Download"),
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Cyan),
-            ),
-            Span::raw(&map.versions[0].download_url),
+            Span::styled(format!("{: <10} -> ", "Key"), theme().header),
+            Span::raw(format!("!bsr {}", map_detail.map.id)),
+            Span::styled("  y to copy it, the hash or a URL", theme().label),
         ]),
         Spans::from(vec![
+            Span::styled(format!("{: <10} -> ", "Open"), theme().header),
             Span::styled(
-                format!("{: <10} -> ", "Cover"),
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Cyan),
+                "o for the BeatSaver page, preview, leaderboard or a player's profile",
+                theme().label,
             ),
-            Span::raw(&map.versions[0].cover_url),
         ]),
     ];
 
@@ -568,64 +1085,196 @@ fn draw_bottom_left_box<B: Backend>(
 
     frame.render_widget(links, chunks[0]);
 
+    draw_versions(frame, map_detail, chunks[1]);
+
+    let table = difficulty_table(&map_detail.version().diffs, |i, diff| {
+        if leaderboard.leaderboard_diffs[i].id != 0 {
+            leaderboard.leaderboard_diffs[i].stars.to_string()
+        } else {
+            map_stars(diff)
+        }
+    });
+
+    frame.render_stateful_widget(table, chunks[2], &mut difficulties_table.table_state);
+    difficulties_table.region.update(
+        chunks[2],
+        difficulties_table.table_state.selected(),
+        difficulties_table.difficulties.len(),
+    );
+}
+
+/// Star rating BeatSaver knows for the difficulty.
+fn map_stars(diff: &MapDifficulty) -> String {
+    match diff.stars {
+        Some(stars) => stars.to_string(),
+        None => "N/A".to_owned(),
+    }
+}
+
+/// The difficulties of a version, with the star rating `stars` returns for each of them.
+fn difficulty_table(
+    diffs: &[MapDifficulty],
+    stars: impl Fn(usize, &MapDifficulty) -> String,
+) -> Table<'static> {
     let header = Row::new(vec![
-        "DIFF", "MODE", "NJS", "NPS", "NOTES", "BOMBS", "STARS",
+        "DIFF", "MODE", "NJS", "NPS", "NOTES", "BOMBS", "LENGTH", "EVENTS", "MODS", "PARITY",
+        "STARS",
     ])
     .height(1)
-    .style(
-        Style::default()
-            .add_modifier(Modifier::BOLD)
-            .fg(Color::Cyan),
-    );
+    .style(theme().header);
 
-    let rows: Vec<Row> = map_detail.map.versions[0]
-        .diffs
+    let rows: Vec<Row> = diffs
         .iter()
         .enumerate()
         .map(|(i, diff)| {
             Row::new(vec![
-                Cell::from(Span::raw(diff.difficulty.to_string())),
+                Cell::from(Span::raw(
+                    diff.label
+                        .clone()
+                        .unwrap_or_else(|| diff.difficulty.to_string()),
+                )),
                 Cell::from(Span::raw(diff.characteristic.to_string())),
                 Cell::from(Span::raw(diff.njs.to_string())),
                 Cell::from(Span::raw(format!("{:.2}", diff.nps))),
                 Cell::from(Span::raw(diff.notes.to_string())),
                 Cell::from(Span::raw(diff.bombs.to_string())),
-                Cell::from(Span::raw(if leaderboard.leaderboard_diffs[i].id == 0 {
-                    "N/A".to_owned()
-                } else {
-                    leaderboard.leaderboard_diffs[i].stars.to_string()
-                })),
+                Cell::from(Span::raw(format_seconds(diff.seconds))),
+                Cell::from(Span::raw(diff.events.to_string())),
+                Cell::from(Span::raw(diff.mods().join(" "))),
+                Cell::from(Span::raw(format!(
+                    "{}E {}W",
+                    diff.parity_summary.errors, diff.parity_summary.warns
+                ))),
+                Cell::from(Span::raw(stars(i, diff))),
             ])
-            .style(Style::default().fg(get_difficulty_color(&diff.difficulty)))
+            .style(theme().difficulty(&diff.difficulty))
         })
         .collect();
 
-    let table = Table::new(rows)
-        .widths(&[Constraint::Percentage(100 / 7); 7])
+    Table::new(rows)
+        .widths(&DIFFICULTY_WIDTHS)
         .header(header)
         .block(Block::default().borders(Borders::ALL).title("Difficulties"))
-        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        .highlight_style(theme().highlight)
+}
+
+/// What's known about a map without fetching anything more, used to preview the selection of
+/// a map list next to it.
+pub fn draw_map_summary<B: Backend>(frame: &mut Frame<B>, map: &Map, area: Rect) {
+    let diffs = map
+        .published_version()
+        .map(|version| version.diffs.as_slice())
+        .unwrap_or_default();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(1),
+                Constraint::Length(diffs.len() as u16 + 3),
+            ]
+            .as_ref(),
+        )
+        .split(area);
 
-    frame.render_stateful_widget(table, chunks[1], &mut difficulties_table.table_state);
+    let mut description = Description::parse(&map.description);
+    draw_top_left_box(
+        frame,
+        map,
+        false,
+        "Description",
+        &mut description,
+        false,
+        chunks[0],
+    );
+    frame.render_widget(
+        difficulty_table(diffs, |_, diff| map_stars(diff)),
+        chunks[1],
+    );
 }
 
-fn draw_top_left_box<B: Backend>(frame: &mut Frame<B>, map: &Map, top_box: Rect) {
-    let song_name_text = Text::from(Span::styled(
-        format!(
-            "  {} {}",
-            &map.metadata.song_name, &map.metadata.song_sub_name
-        ),
-        Style::default()
-            .fg(Color::LightMagenta)
-            .add_modifier(Modifier::BOLD),
-    ));
+fn draw_versions<B: Backend>(frame: &mut Frame<B>, map_detail: &MapDetail, area: Rect) {
+    let versions = &map_detail.map.versions;
+
+    let rows: Vec<Row> = versions
+        .iter()
+        .enumerate()
+        .map(|(i, version)| {
+            Row::new(vec![
+                Cell::from(format!("v{}", versions.len() - i)),
+                Cell::from(format!("{:?}", version.state)),
+                Cell::from(
+                    version
+                        .created_at
+                        .map(|date| date.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default(),
+                ),
+                Cell::from(version.hash.to_owned()),
+            ])
+        })
+        .collect();
+
+    let title = if versions[0].state != VersionState::Published {
+        Spans::from(vec![
+            Span::raw("Versions "),
+            Span::styled(
+                format!("- latest version is {:?}, not published", versions[0].state),
+                theme().error.add_modifier(Modifier::BOLD),
+            ),
+        ])
+    } else {
+        Spans::from("Versions")
+    };
+
+    let table = Table::new(rows)
+        .widths(&[
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Length(11),
+            Constraint::Min(1),
+        ])
+        .highlight_style(theme().highlight)
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    // a fresh state keeps the selected version in view without tracking a scroll offset
+    let mut table_state = TableState::default();
+    table_state.select(Some(map_detail.selected_version));
+
+    frame.render_stateful_widget(table, area, &mut table_state);
+}
+
+fn draw_top_left_box<B: Backend>(
+    frame: &mut Frame<B>,
+    map: &Map,
+    offline: bool,
+    description_title: &str,
+    description: &mut Description,
+    focused: bool,
+    top_box: Rect,
+) -> Rect {
+    let mut tags = vec![Span::raw("  ")];
+    for tag in &map.tags {
+        tags.push(Span::styled(format!("[{}]", tag), tag_style(tag)));
+        tags.push(Span::raw(" "));
+    }
+
+    let song_name_text = Text::from(vec![
+        Spans::from(Span::styled(
+            format!(
+                "  {} {}",
+                &map.metadata.song_name, &map.metadata.song_sub_name
+            ),
+            theme().title,
+        )),
+        Spans::from(tags),
+    ]);
 
     let top_left_box = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
             [
-                Constraint::Length(3),
-                Constraint::Length(7),
+                Constraint::Length(4),
+                Constraint::Length(10),
                 Constraint::Min(1),
             ]
             .as_ref(),
@@ -634,7 +1283,11 @@ fn draw_top_left_box<B: Backend>(frame: &mut Frame<B>, map: &Map, top_box: Rect)
 
     let song_name = Paragraph::new(song_name_text)
         .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title("Song Name"));
+        .block(Block::default().borders(Borders::ALL).title(if offline {
+            "Song Name - OFFLINE, showing cached data"
+        } else {
+            "Song Name"
+        }));
 
     frame.render_widget(song_name, top_left_box[0]);
 
@@ -643,68 +1296,55 @@ fn draw_top_left_box<B: Backend>(frame: &mut Frame<B>, map: &Map, top_box: Rect)
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
         .split(top_left_box[1]);
 
-    let box_width = if top_left_middle_box[0].width <= 25 {
-        0
-    } else {
-        (top_left_middle_box[0].width as usize / 2 - 3)
-            + ((top_left_middle_box[0].width as usize / 2 - 3) - 10)
-    };
-
-    let song_info = vec![
-        Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "Artist"),
-                Style::default().fg(Color::Cyan),
-            ),
-            Span::raw(format!(
-                "{: >box_width$}",
-                truncate(&map.metadata.song_author_name, box_width),
-            )),
-        ]),
-        Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "Mapper"),
-                Style::default().fg(Color::Cyan),
-            ),
-            Span::raw(format!(
-                "{: >box_width$}",
-                truncate(&map.metadata.level_author_name, box_width),
-            )),
-        ]),
-        Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "BPM"),
-                Style::default().fg(Color::Cyan),
-            ),
-            Span::raw(format!("{: >box_width$}", &map.metadata.bpm,)),
-        ]),
-        Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "Published"),
-                Style::default().fg(Color::Cyan),
-            ),
-            Span::raw(format!(
-                "{: >box_width$}",
-                truncate(
-                    map.last_published_at.split('T').collect::<Vec<_>>()[0],
-                    box_width
-                ),
-            )),
-        ]),
-        Spans::from(vec![
-            Span::styled(
-                format!("{: <10} -> ", "Duration"),
-                Style::default().fg(Color::Cyan),
-            ),
-            Span::raw(format!(
-                "{: >box_width$}",
-                (&map.metadata.duration / 60).to_string()
-                    + ":"
-                    + &(&map.metadata.duration % 60).to_string()
-            )),
-        ]),
+    // what's left of the box next to the borders and the labels
+    let box_width = (top_left_middle_box[0].width as usize).saturating_sub(16);
+
+    let mut song_info = vec![
+        info_line("Artist", &map.metadata.song_author_name, box_width),
+        info_line("Mapper", &map.metadata.level_author_name, box_width),
+        info_line("BPM", &map.metadata.bpm.to_string(), box_width),
+        info_line(
+            "Duration",
+            &format_seconds(map.metadata.duration as f32),
+            box_width,
+        ),
+        info_line(
+            "Published",
+            &map.last_published_at.format("%Y-%m-%d").to_string(),
+            box_width,
+        ),
     ];
 
+    if let Some(uploaded) = map.uploaded {
+        song_info.push(info_line(
+            "Uploaded",
+            &uploaded.format("%Y-%m-%d").to_string(),
+            box_width,
+        ));
+    }
+
+    if let Some(curated_at) = map.curated_at {
+        let curator = map
+            .curator
+            .as_ref()
+            .map(|curator| format!(" by {}", curator.name))
+            .unwrap_or_default();
+
+        song_info.push(info_line(
+            "Curated",
+            &format!("{}{}", curated_at.format("%Y-%m-%d"), curator),
+            box_width,
+        ));
+    }
+
+    if map.declared_ai != DeclaredAi::None {
+        song_info.push(info_line(
+            "AI",
+            &format!("declared by {:?}", map.declared_ai),
+            box_width,
+        ));
+    }
+
     let song_info = Paragraph::new(song_info)
         .block(Block::default().borders(Borders::ALL).title("Song Info"))
         .wrap(Wrap { trim: true })
@@ -739,6 +1379,19 @@ fn draw_top_left_box<B: Backend>(frame: &mut Frame<B>, map: &Map, top_box: Rect)
             "{: <stats_width$} -> {: >stats_width$}",
             "Downvotes", &map.stats.downvotes
         ))),
+        Spans::from(Span::raw(format!(
+            "{: <stats_width$} -> {: >stats_width$}",
+            "Rating",
+            format!("{:.1}%", map.stats.score * 100.0)
+        ))),
+        Spans::from(Span::raw(format!(
+            "{: <stats_width$} -> {: >stats_width$}",
+            "Plays", &map.stats.plays
+        ))),
+        Spans::from(Span::raw(format!(
+            "{: <stats_width$} -> {: >stats_width$}",
+            "Downloads", &map.stats.downloads
+        ))),
     ];
 
     let stats = Paragraph::new(stats)
@@ -747,13 +1400,16 @@ fn draw_top_left_box<B: Backend>(frame: &mut Frame<B>, map: &Map, top_box: Rect)
 
     frame.render_widget(stats, top_left_middle_box[1]);
 
-    let desc = Paragraph::new(map.description.clone())
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Description (e to expand)"),
-        )
-        .wrap(Wrap { trim: true });
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(description_title);
+    if focused {
+        block = block.border_style(theme().label);
+    }
+
+    let text_area = block.inner(top_left_box[2]);
+    let lines = description.visible_lines(text_area.width, text_area.height);
+    frame.render_widget(Paragraph::new(lines).block(block), top_left_box[2]);
 
-    frame.render_widget(desc, top_left_box[2]);
+    top_left_box[2]
 }
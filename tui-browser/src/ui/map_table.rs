@@ -0,0 +1,488 @@
+use std::collections::{HashMap, HashSet};
+
+use common::{
+    api::paginator::Paginator,
+    filter::FuzzyMatch,
+    sort::{Sort, SortDirection, SortKey},
+    types::map::{tag_kind, Map, TagKind},
+};
+use serde::{Deserialize, Serialize};
+use tui::{
+    layout::Constraint,
+    style::Style,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Cell, Row, Table},
+};
+
+use crate::{theme::theme, utils::text::ellipsize};
+
+/// Columns the map tables can show.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Id,
+    SongName,
+    SongAuthor,
+    LevelAuthor,
+    Date,
+    Tags,
+    Upvotes,
+    Downvotes,
+    Rating,
+    Plays,
+    Downloads,
+    Bpm,
+    Duration,
+    MaxNps,
+    Stars,
+    Ranked,
+}
+
+impl Column {
+    pub const DEFAULT: [Column; 5] = [
+        Column::Id,
+        Column::SongName,
+        Column::SongAuthor,
+        Column::LevelAuthor,
+        Column::Date,
+    ];
+
+    pub const ALL: [Column; 16] = [
+        Column::Id,
+        Column::SongName,
+        Column::SongAuthor,
+        Column::LevelAuthor,
+        Column::Date,
+        Column::Tags,
+        Column::Upvotes,
+        Column::Downvotes,
+        Column::Rating,
+        Column::Plays,
+        Column::Downloads,
+        Column::Bpm,
+        Column::Duration,
+        Column::MaxNps,
+        Column::Stars,
+        Column::Ranked,
+    ];
+
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::SongName => "SONG NAME",
+            Column::SongAuthor => "SONG AUTHOR",
+            Column::LevelAuthor => "LEVEL AUTHOR",
+            Column::Date => "DATE",
+            Column::Tags => "TAGS",
+            Column::Upvotes => "UP",
+            Column::Downvotes => "DOWN",
+            Column::Rating => "RATING",
+            Column::Plays => "PLAYS",
+            Column::Downloads => "DLS",
+            Column::Bpm => "BPM",
+            Column::Duration => "LENGTH",
+            Column::MaxNps => "NPS",
+            Column::Stars => "STARS",
+            Column::Ranked => "RANKED",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Id => "ID",
+            Column::SongName => "Song name",
+            Column::SongAuthor => "Artist",
+            Column::LevelAuthor => "Mapper",
+            Column::Date => "Date",
+            Column::Tags => "Tags",
+            Column::Upvotes => "Upvotes",
+            Column::Downvotes => "Downvotes",
+            Column::Rating => "Rating",
+            Column::Plays => "Plays",
+            Column::Downloads => "Downloads",
+            Column::Bpm => "BPM",
+            Column::Duration => "Duration",
+            Column::MaxNps => "Max NPS",
+            Column::Stars => "Stars",
+            Column::Ranked => "Ranked",
+        }
+    }
+
+    /// Key that toggles the column, its uppercase version sorts by it.
+    pub fn key(&self) -> char {
+        match self {
+            Column::Id => 'i',
+            Column::SongName => 'n',
+            Column::SongAuthor => 'a',
+            Column::LevelAuthor => 'm',
+            Column::Date => 'd',
+            Column::Tags => 'g',
+            Column::Upvotes => 'u',
+            Column::Downvotes => 'v',
+            Column::Rating => 'r',
+            Column::Plays => 'p',
+            Column::Downloads => 'l',
+            Column::Bpm => 'b',
+            Column::Duration => 't',
+            Column::MaxNps => 'x',
+            Column::Stars => 's',
+            Column::Ranked => 'k',
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Column> {
+        Column::ALL.into_iter().find(|column| column.key() == key)
+    }
+
+    /// Width of the column relative to the others, unless the user changed it.
+    pub fn default_width(&self) -> u16 {
+        match self {
+            Column::Id => 10,
+            Column::SongName => 30,
+            Column::SongAuthor => 30,
+            Column::LevelAuthor => 20,
+            Column::Date => 10,
+            Column::Tags => 25,
+            Column::Upvotes
+            | Column::Downvotes
+            | Column::Rating
+            | Column::Plays
+            | Column::Downloads
+            | Column::Bpm
+            | Column::Duration
+            | Column::MaxNps
+            | Column::Stars
+            | Column::Ranked => 7,
+        }
+    }
+
+    /// Narrowest the column is worth showing at.
+    fn min_width(&self) -> u16 {
+        match self {
+            Column::Id => 7,
+            Column::SongName => 12,
+            Column::SongAuthor | Column::LevelAuthor | Column::Date | Column::Tags => 10,
+            Column::Ranked => 9,
+            // room for the sort arrow
+            _ => self.header().len() as u16 + 2,
+        }
+    }
+
+    /// How long the column stays when the table is too narrow for all of them, the lowest
+    /// priority is hidden first.
+    fn priority(&self) -> u8 {
+        match self {
+            Column::SongName => 15,
+            Column::LevelAuthor => 14,
+            Column::Id => 13,
+            Column::Rating => 12,
+            Column::Date => 11,
+            Column::Stars => 10,
+            Column::Ranked => 9,
+            Column::Duration => 8,
+            Column::Bpm => 7,
+            Column::MaxNps => 6,
+            Column::Upvotes => 5,
+            Column::SongAuthor => 4,
+            Column::Downloads => 3,
+            Column::Plays => 2,
+            Column::Downvotes => 1,
+            Column::Tags => 0,
+        }
+    }
+
+    /// The map's cell, with text longer than `width` ellipsized.
+    fn cell(&self, map: &Map, fuzzy: Option<&FuzzyMatch>, width: usize) -> Cell<'static> {
+        let matched = |indices: fn(&FuzzyMatch) -> &Vec<usize>| {
+            fuzzy.map(indices).map(Vec::as_slice).unwrap_or_default()
+        };
+
+        // matches past the cut aren't shown, and the ellipsis in their place isn't one
+        let text = |text: &str, indices: &[usize]| {
+            let shown = ellipsize(text, width);
+            let kept = if shown == text {
+                usize::MAX
+            } else {
+                shown.chars().count().saturating_sub(1)
+            };
+            let indices: Vec<usize> = indices.iter().copied().filter(|&i| i < kept).collect();
+            highlighted(&shown, &indices)
+        };
+
+        match self {
+            Column::Id => text(&map.id, matched(|m| &m.id)),
+            Column::SongName => text(&map.metadata.song_name, matched(|m| &m.song_name)),
+            Column::SongAuthor => text(&map.metadata.song_author_name, matched(|m| &m.song_author)),
+            Column::LevelAuthor => text(
+                &map.metadata.level_author_name,
+                matched(|m| &m.level_author),
+            ),
+            Column::Date => Cell::from(map.last_published_at.format("%Y-%m-%d").to_string()),
+            Column::Tags => Cell::from(Spans::from(
+                map.tags
+                    .iter()
+                    .map(|tag| Span::styled(format!("{} ", tag), tag_style(tag)))
+                    .collect::<Vec<_>>(),
+            )),
+            Column::Upvotes => Cell::from(map.stats.upvotes.to_string()),
+            Column::Downvotes => Cell::from(map.stats.downvotes.to_string()),
+            Column::Rating => Cell::from(format!("{:.1}%", map.stats.score * 100.0)),
+            Column::Plays => Cell::from(map.stats.plays.to_string()),
+            Column::Downloads => Cell::from(map.stats.downloads.to_string()),
+            Column::Bpm => Cell::from(format!("{:.0}", map.metadata.bpm)),
+            Column::Duration => Cell::from(format!(
+                "{}:{:02}",
+                map.metadata.duration / 60,
+                map.metadata.duration % 60
+            )),
+            Column::MaxNps => Cell::from(format!("{:.2}", map.max_nps())),
+            Column::Stars => Cell::from(
+                map.max_stars()
+                    .map(|stars| format!("{:.2}", stars))
+                    .unwrap_or_default(),
+            ),
+            Column::Ranked => Cell::from(if map.ranked {
+                "Ranked"
+            } else if map.qualified {
+                "Qualified"
+            } else {
+                ""
+            })
+            .style(theme().ranked),
+        }
+    }
+}
+
+/// Cell with the characters at `indices` highlighted, e.g. the ones a filter matched.
+pub fn highlighted(text: &str, indices: &[usize]) -> Cell<'static> {
+    if indices.is_empty() {
+        return Cell::from(text.to_owned());
+    }
+
+    let highlight = theme().matched;
+
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+
+    for (i, c) in text.chars().enumerate() {
+        let matched = indices.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            let style = if run_matched {
+                highlight
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    let style = if run_matched {
+        highlight
+    } else {
+        Style::default()
+    };
+    spans.push(Span::styled(run, style));
+
+    Cell::from(Spans::from(spans))
+}
+
+/// A column shown in a table and how wide it is relative to the other columns.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ColumnLayout {
+    pub column: Column,
+    pub width: u16,
+}
+
+impl ColumnLayout {
+    pub fn new(column: Column) -> ColumnLayout {
+        ColumnLayout {
+            column,
+            width: column.default_width(),
+        }
+    }
+
+    pub fn defaults() -> Vec<ColumnLayout> {
+        Column::DEFAULT.into_iter().map(ColumnLayout::new).collect()
+    }
+}
+
+/// How sort keys show up in the tables.
+pub trait SortKeyColumn {
+    /// The column showing the value that is sorted by.
+    fn column(&self) -> Column;
+
+    /// Key that sorts by this, the uppercase key of its column.
+    fn key(&self) -> char;
+}
+
+impl SortKeyColumn for SortKey {
+    fn column(&self) -> Column {
+        match self {
+            SortKey::Id => Column::Id,
+            SortKey::SongName => Column::SongName,
+            SortKey::SongAuthor => Column::SongAuthor,
+            SortKey::LevelAuthor => Column::LevelAuthor,
+            SortKey::Date => Column::Date,
+            SortKey::Upvotes => Column::Upvotes,
+            SortKey::Downvotes => Column::Downvotes,
+            SortKey::Rating => Column::Rating,
+            SortKey::Plays => Column::Plays,
+            SortKey::Downloads => Column::Downloads,
+            SortKey::Bpm => Column::Bpm,
+            SortKey::Duration => Column::Duration,
+            SortKey::MaxNps => Column::MaxNps,
+            SortKey::Stars => Column::Stars,
+            SortKey::Ranked => Column::Ranked,
+        }
+    }
+
+    fn key(&self) -> char {
+        self.column().key().to_ascii_uppercase()
+    }
+}
+
+/// The sort key picked with `key`, see [`SortKeyColumn::key`].
+pub fn sort_key_for(key: char) -> Option<SortKey> {
+    SortKey::ALL
+        .into_iter()
+        .find(|sort_key| sort_key.key() == key)
+}
+
+fn sort_arrow(sort: &Sort) -> &'static str {
+    match sort.direction {
+        SortDirection::Ascending => "↑",
+        SortDirection::Descending => "↓",
+    }
+}
+
+/// The sort's column and direction, like `Stars ↓`.
+pub fn sort_label(sort: &Sort) -> String {
+    format!("{} {}", sort.key.column().name(), sort_arrow(sort))
+}
+
+/// Table title suffix like `page 2 / 415 results`.
+pub fn page_status(paginator: &Paginator) -> String {
+    match paginator.total() {
+        Some(total) => format!("page {} / {} results", paginator.pages_loaded(), total),
+        None => format!("page {}", paginator.pages_loaded()),
+    }
+}
+
+pub fn tag_style(tag: &str) -> Style {
+    match tag_kind(tag) {
+        TagKind::Style => theme().style_tag,
+        TagKind::Genre => theme().genre_tag,
+    }
+}
+
+/// The columns that fit in a table `width` wide, leaving out the ones of the lowest priority
+/// until the rest get at least their minimum width.
+pub fn fit_columns(columns: &[ColumnLayout], width: u16) -> Vec<ColumnLayout> {
+    let mut fitted = columns.to_vec();
+
+    while fitted.len() > 1 && min_table_width(&fitted) > width {
+        let lowest = fitted
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, layout)| layout.column.priority())
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        fitted.remove(lowest);
+    }
+    fitted
+}
+
+/// Width a table needs to show every column at its minimum width, with borders and spacing.
+fn min_table_width(columns: &[ColumnLayout]) -> u16 {
+    let min: u16 = columns.iter().map(|layout| layout.column.min_width()).sum();
+    min + columns.len() as u16 + 1
+}
+
+/// Splits the width of a table `width` wide between the columns: each gets its minimum width
+/// and the rest is shared according to their relative widths.
+pub fn column_widths(columns: &[ColumnLayout], width: u16) -> Vec<Constraint> {
+    let total: u32 = columns
+        .iter()
+        .map(|layout| layout.width as u32)
+        .sum::<u32>()
+        .max(1);
+    let extra = width.saturating_sub(min_table_width(columns)) as u32;
+
+    let mut widths: Vec<u16> = columns
+        .iter()
+        .map(|layout| layout.column.min_width() + (extra * layout.width as u32 / total) as u16)
+        .collect();
+
+    // rounding leftovers go to the widest column
+    let used: u16 = widths.iter().sum::<u16>() + columns.len() as u16 + 1;
+    if let Some(widest) = (0..columns.len()).max_by_key(|&i| columns[i].width) {
+        widths[widest] += width.saturating_sub(used);
+    }
+
+    widths.into_iter().map(Constraint::Length).collect()
+}
+
+/// Column header, with an arrow and its position in the sort stack if the maps are sorted by it.
+fn header_cell(column: Column, sort: &[Sort]) -> Cell<'static> {
+    match sort.iter().position(|s| s.key.column() == column) {
+        Some(i) if sort.len() > 1 => Cell::from(format!(
+            "{} {}{}",
+            column.header(),
+            sort_arrow(&sort[i]),
+            i + 1
+        )),
+        Some(i) => Cell::from(format!("{} {}", column.header(), sort_arrow(&sort[i]))),
+        None => Cell::from(column.header()),
+    }
+}
+
+pub fn display_maps<'a>(
+    maps: &[Map],
+    columns: &[ColumnLayout],
+    widths: &'a [Constraint],
+    sort: &[Sort],
+    matches: &HashMap<String, FuzzyMatch>,
+    marked: &HashSet<String>,
+    title: String,
+) -> Table<'a> {
+    let header = Row::new(
+        columns
+            .iter()
+            .map(|layout| header_cell(layout.column, sort))
+            .collect::<Vec<_>>(),
+    )
+    .style(theme().header);
+
+    let rows: Vec<Row> = maps
+        .iter()
+        .map(|m| {
+            let row = Row::new(
+                columns
+                    .iter()
+                    .zip(widths)
+                    .map(|(layout, width)| {
+                        let width = match width {
+                            Constraint::Length(width) => *width as usize,
+                            _ => usize::MAX,
+                        };
+                        layout.column.cell(m, matches.get(&m.id), width)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+
+            if marked.contains(&m.id) {
+                row.style(theme().marked)
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    Table::new(rows)
+        .widths(widths)
+        .header(header)
+        .highlight_style(theme().highlight)
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+use common::{
+    api::{
+        beatsaver::fetch_uploader,
+        paginator::{PageSource, Paginator},
+    },
+    types::map::{Map, Uploader},
+};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Paragraph, TableState},
+    Frame, Terminal,
+};
+
+use crate::{
+    theme::theme,
+    utils::{
+        loading::Loading,
+        terminal::{interrupt, is_interrupt},
+    },
+};
+
+use super::{
+    layout::draw_too_small,
+    map_table::{column_widths, display_maps, fit_columns, page_status, ColumnLayout},
+    notifications::{draw_toasts, notify_error},
+};
+
+struct MapperProfile {
+    uploader: Uploader,
+    maps: Vec<Map>,
+    table_state: TableState,
+    paginator: Paginator,
+}
+
+/// How many rows before the end of the maps the next page is fetched.
+const PREFETCH_DISTANCE: usize = 5;
+
+impl MapperProfile {
+    async fn new(uploader_id: i32) -> Result<MapperProfile, Box<dyn Error>> {
+        let uploader = fetch_uploader(uploader_id).await?;
+        let mut paginator = Paginator::new(PageSource::Uploader(uploader_id));
+        let maps = paginator.fetch_next().await?;
+
+        Ok(MapperProfile {
+            uploader,
+            maps,
+            table_state: TableState::default(),
+            paginator,
+        })
+    }
+
+    async fn fetch_more(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut maps = self.paginator.fetch_next().await?;
+        self.maps.append(&mut maps);
+        Ok(())
+    }
+
+    fn needs_more(&self) -> bool {
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.paginator.has_more() && selected + PREFETCH_DISTANCE >= self.maps.len()
+    }
+
+    fn next_item(&mut self) {
+        if self.maps.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(i) => {
+                if i >= self.maps.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => 0,
+        };
+        self.table_state.select(Some(i));
+    }
+
+    fn previous_item(&mut self) {
+        if self.maps.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.maps.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.table_state.select(Some(i));
+    }
+}
+
+/// Lists the mapper's maps, returning the id of the one the user picked to open.
+pub async fn start_mapper_profile<B: Backend>(
+    terminal: &mut Terminal<B>,
+    uploader_id: i32,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+
+    let mut profile = MapperProfile::new(uploader_id).await?;
+
+    spinner.stop();
+
+    loop {
+        terminal.draw(|frame| draw_mapper_profile(frame, &mut profile))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if is_interrupt(&key) {
+                interrupt();
+            }
+
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Down => {
+                    profile.next_item();
+
+                    if profile.needs_more() {
+                        fetch_more(terminal, &mut profile).await;
+                    }
+                }
+                KeyCode::Up => profile.previous_item(),
+                KeyCode::Char('F') if profile.paginator.has_more() => {
+                    fetch_more(terminal, &mut profile).await
+                }
+                KeyCode::Enter => {
+                    if let Some(selected) = profile.table_state.selected() {
+                        return Ok(Some(profile.maps[selected].id.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+async fn fetch_more<B: Backend>(terminal: &mut Terminal<B>, profile: &mut MapperProfile) {
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+
+    let response = profile.fetch_more().await;
+    spinner.stop();
+
+    if let Err(e) = response {
+        notify_error("Failed to fetch the mapper's maps", e.as_ref());
+    }
+}
+
+fn draw_mapper_profile<B: Backend>(frame: &mut Frame<B>, profile: &mut MapperProfile) {
+    if draw_too_small(frame) {
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Length(5),
+                Constraint::Min(1),
+            ]
+            .as_ref(),
+        )
+        .split(frame.size());
+
+    frame.render_widget(
+        Paragraph::new(Text::from(Spans::from(Span::raw(
+            "Go Back(Esc) Open map(Enter) Fetch more(F)",
+        )))),
+        chunks[0],
+    );
+
+    draw_mapper_stats(frame, &profile.uploader, chunks[1]);
+
+    let columns = fit_columns(&ColumnLayout::defaults(), chunks[2].width);
+    let widths = column_widths(&columns, chunks[2].width);
+    frame.render_stateful_widget(
+        display_maps(
+            &profile.maps,
+            &columns,
+            &widths,
+            &[],
+            &HashMap::new(),
+            &HashSet::new(),
+            format!(
+                "Maps by {} - {}",
+                profile.uploader.name,
+                page_status(&profile.paginator)
+            ),
+        ),
+        chunks[2],
+        &mut profile.table_state,
+    );
+    draw_toasts(frame);
+}
+
+fn draw_mapper_stats<B: Backend>(frame: &mut Frame<B>, uploader: &Uploader, area: Rect) {
+    let label_style = theme().label;
+
+    let mut name = vec![Span::styled(uploader.name.to_owned(), theme().title)];
+    if uploader.verified_mapper {
+        name.push(Span::styled(" (verified mapper)", theme().success));
+    }
+
+    let mut lines = vec![Spans::from(name)];
+
+    if let Some(stats) = &uploader.stats {
+        lines.push(Spans::from(vec![
+            Span::styled(format!("{: <10} -> ", "Maps"), label_style),
+            Span::raw(format!("{: <10}", stats.total_maps)),
+            Span::styled(format!("{: <10} -> ", "Ranked"), label_style),
+            Span::raw(stats.ranked_maps.to_string()),
+        ]));
+        let rating = format!("{:.1}%", stats.avg_score * 100.0);
+        lines.push(Spans::from(vec![
+            Span::styled(format!("{: <10} -> ", "Rating"), label_style),
+            Span::raw(format!("{: <10}", rating)),
+            Span::styled(format!("{: <10} -> ", "Avg BPM"), label_style),
+            Span::raw(format!("{:.0}", stats.avg_bpm)),
+        ]));
+    }
+
+    let stats = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Mapper"));
+
+    frame.render_widget(stats, area);
+}
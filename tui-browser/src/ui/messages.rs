@@ -0,0 +1,177 @@
+use std::io;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{
+    theme::theme,
+    utils::terminal::{interrupt, is_interrupt},
+};
+
+use super::{
+    layout::draw_too_small,
+    notifications::{dismiss_all, history, Notification},
+};
+
+struct MessageList {
+    /// Newest first.
+    messages: Vec<Notification>,
+    table_state: TableState,
+    /// Whether the causes of the selected message are shown.
+    expanded: bool,
+}
+
+impl MessageList {
+    fn selected(&self) -> Option<&Notification> {
+        self.messages.get(self.table_state.selected()?)
+    }
+
+    fn next_item(&mut self) {
+        if self.messages.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(i) if i + 1 < self.messages.len() => i + 1,
+            _ => 0,
+        };
+        self.table_state.select(Some(i));
+    }
+
+    fn previous_item(&mut self) {
+        if self.messages.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(0) | None => self.messages.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.table_state.select(Some(i));
+    }
+}
+
+/// Lists every notification so far until it's closed, dismissing the toasts still shown.
+pub fn start_messages<B: Backend>(terminal: &mut Terminal<B>) -> Result<(), io::Error> {
+    dismiss_all();
+
+    let mut list = MessageList {
+        messages: history().into_iter().rev().collect(),
+        table_state: TableState::default(),
+        expanded: false,
+    };
+    list.next_item();
+
+    loop {
+        terminal.draw(|frame| draw_messages(frame, &mut list))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if is_interrupt(&key) {
+                interrupt();
+            }
+
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('M') => return Ok(()),
+                KeyCode::Down => list.next_item(),
+                KeyCode::Up => list.previous_item(),
+                KeyCode::Enter => list.expanded = !list.expanded,
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw_messages<B: Backend>(frame: &mut Frame<B>, list: &mut MessageList) {
+    if draw_too_small(frame) {
+        return;
+    }
+
+    let details_height = match list.selected() {
+        Some(selected) if list.expanded => selected.causes.len().max(1) as u16 + 3,
+        _ => 0,
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints(
+            [
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(details_height),
+            ]
+            .as_ref(),
+        )
+        .split(frame.size());
+
+    frame.render_widget(
+        Paragraph::new(Text::from(Spans::from(Span::raw(
+            "Go Back(Esc) Show causes(Enter)",
+        )))),
+        chunks[0],
+    );
+
+    let header = Row::new(["TIME", "LEVEL", "MESSAGE"]).style(theme().header);
+
+    let rows: Vec<Row> = list
+        .messages
+        .iter()
+        .map(|message| {
+            Row::new(vec![
+                Cell::from(message.time.format("%H:%M:%S").to_string()),
+                Cell::from(message.severity.label()).style(message.severity.style()),
+                Cell::from(message.message.clone()),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .header(header)
+        .widths(&[
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Min(1),
+        ])
+        .highlight_style(theme().highlight)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if list.messages.is_empty() {
+                    "No messages yet"
+                } else {
+                    "Messages"
+                }),
+        );
+
+    frame.render_stateful_widget(table, chunks[1], &mut list.table_state);
+
+    if let Some(selected) = list.selected().filter(|_| list.expanded) {
+        let mut lines = vec![Spans::from(selected.message.clone())];
+        if selected.causes.is_empty() {
+            lines.push(Spans::from("No further causes"));
+        }
+        lines.extend(
+            selected
+                .causes
+                .iter()
+                .map(|cause| Spans::from(format!("caused by: {}", cause))),
+        );
+
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).title("Details")),
+            chunks[2],
+        );
+    }
+}
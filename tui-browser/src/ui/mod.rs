@@ -1,2 +1,12 @@
+pub mod command_palette;
+pub mod description;
+pub mod help;
+pub mod layout;
+pub mod log_panel;
 pub mod map_browser;
 pub mod map_detail;
+pub mod map_table;
+pub mod mapper_profile;
+pub mod messages;
+pub mod notifications;
+pub mod saved_searches;
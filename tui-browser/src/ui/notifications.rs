@@ -0,0 +1,174 @@
+use std::{
+    error::Error,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::Span,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::theme::theme;
+
+/// How long info toasts stay on screen, the others stay until they're dismissed.
+const INFO_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Most notifications kept for the messages panel, the oldest are dropped first.
+const MAX_NOTIFICATIONS: usize = 200;
+
+/// Most toasts shown at once, the newest ones.
+const MAX_TOASTS: usize = 3;
+
+const TOAST_WIDTH: u16 = 50;
+
+static NOTIFICATIONS: Mutex<Vec<Notification>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+
+    pub fn style(&self) -> Style {
+        match self {
+            Severity::Info => theme().label,
+            Severity::Warning => theme().warning,
+            Severity::Error => theme().error,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    /// What caused the error, outermost first.
+    pub causes: Vec<String>,
+    pub time: DateTime<Local>,
+    shown_at: Instant,
+    dismissed: bool,
+}
+
+impl Notification {
+    fn is_toast(&self) -> bool {
+        !self.dismissed
+            && (self.severity != Severity::Info || self.shown_at.elapsed() < INFO_TIMEOUT)
+    }
+}
+
+fn notifications() -> MutexGuard<'static, Vec<Notification>> {
+    NOTIFICATIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn push(severity: Severity, message: String, causes: Vec<String>) {
+    let mut notifications = notifications();
+
+    notifications.push(Notification {
+        severity,
+        message,
+        causes,
+        time: Local::now(),
+        shown_at: Instant::now(),
+        dismissed: false,
+    });
+
+    let excess = notifications.len().saturating_sub(MAX_NOTIFICATIONS);
+    notifications.drain(..excess);
+}
+
+/// Shows the message in a toast without interrupting what the user is doing.
+pub fn notify(severity: Severity, message: impl Into<String>) {
+    push(severity, message.into(), Vec::new());
+}
+
+/// Shows `context: error` in an error toast, keeping the error's sources for the messages panel.
+pub fn notify_error(context: &str, error: &dyn Error) {
+    let mut causes = Vec::new();
+    let mut source = error.source();
+    while let Some(error) = source {
+        causes.push(error.to_string());
+        source = error.source();
+    }
+
+    push(Severity::Error, format!("{}: {}", context, error), causes);
+}
+
+/// Every notification so far, oldest first.
+pub fn history() -> Vec<Notification> {
+    notifications().clone()
+}
+
+/// Hides the toasts that are still shown.
+pub fn dismiss_all() {
+    for notification in notifications().iter_mut() {
+        notification.dismissed = true;
+    }
+}
+
+/// Draws the newest toasts stacked up from the bottom right corner of the frame.
+pub fn draw_toasts<B: Backend>(frame: &mut Frame<B>) {
+    let toasts: Vec<Notification> = notifications()
+        .iter()
+        .rev()
+        .filter(|notification| notification.is_toast())
+        .take(MAX_TOASTS)
+        .cloned()
+        .collect();
+
+    let size = frame.size();
+    let width = TOAST_WIDTH.min(size.width.saturating_sub(2));
+    let text_width = width.saturating_sub(2).max(1);
+    let mut bottom = size.bottom().saturating_sub(1);
+
+    for toast in toasts {
+        // word wrapping may need a line more than the division says
+        let lines = (toast.message.width() as u16).div_ceil(text_width) + 1;
+        let height = (lines + 2).min(bottom.saturating_sub(size.y));
+        if height < 3 {
+            break;
+        }
+
+        let area = Rect {
+            x: size.right().saturating_sub(width + 1),
+            y: bottom - height,
+            width,
+            height,
+        };
+        bottom = area.y;
+
+        let title = match toast.severity {
+            Severity::Info => toast.severity.label().to_owned(),
+            _ => format!("{} - z to dismiss, M for messages", toast.severity.label()),
+        };
+        let paragraph = Paragraph::new(toast.message)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(toast.severity.style())
+                    .title(Span::styled(title, toast.severity.style())),
+            );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+}
@@ -0,0 +1,214 @@
+use std::error::Error;
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+
+use common::store::searches::{SavedSearch, Searches};
+
+use crate::{
+    theme::theme,
+    utils::{
+        loading::Loading,
+        terminal::{interrupt, is_interrupt},
+    },
+};
+
+use super::{
+    layout::draw_too_small,
+    map_table::sort_label,
+    notifications::{draw_toasts, notify_error},
+};
+
+struct SavedSearchList {
+    searches: Searches,
+    /// Maps published since the last run of each saved search, `None` if not checked.
+    new_maps: Vec<Option<usize>>,
+    table_state: TableState,
+}
+
+impl SavedSearchList {
+    fn selected(&self) -> Option<&SavedSearch> {
+        self.searches.saved.get(self.table_state.selected()?)
+    }
+
+    fn next_item(&mut self) {
+        if self.searches.saved.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(i) if i + 1 < self.searches.saved.len() => i + 1,
+            _ => 0,
+        };
+        self.table_state.select(Some(i));
+    }
+
+    fn previous_item(&mut self) {
+        if self.searches.saved.is_empty() {
+            return;
+        }
+
+        let i = match self.table_state.selected() {
+            Some(0) | None => self.searches.saved.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.table_state.select(Some(i));
+    }
+}
+
+/// Counts the new maps of every saved search that asked to be notified about them.
+pub async fn check_new_maps(searches: &Searches) -> Vec<Option<usize>> {
+    let mut new_maps = Vec::new();
+
+    for search in &searches.saved {
+        new_maps.push(if search.notify {
+            search.count_new().await.ok()
+        } else {
+            None
+        });
+    }
+    new_maps
+}
+
+/// Lists the saved searches, returning the one the user picked to run.
+pub async fn start_saved_searches<B: Backend>(
+    terminal: &mut Terminal<B>,
+) -> Result<Option<SavedSearch>, Box<dyn Error>> {
+    let searches = Searches::load()?;
+
+    let mut spinner = Loading::new(terminal);
+    spinner.start();
+    let new_maps = check_new_maps(&searches).await;
+    spinner.stop();
+
+    let mut list = SavedSearchList {
+        searches,
+        new_maps,
+        table_state: TableState::default(),
+    };
+    list.next_item();
+
+    loop {
+        terminal.draw(|frame| draw_saved_searches(frame, &mut list))?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if is_interrupt(&key) {
+                interrupt();
+            }
+
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Down => list.next_item(),
+                KeyCode::Up => list.previous_item(),
+                KeyCode::Enter => return Ok(list.selected().cloned()),
+                KeyCode::Char('d') => {
+                    let selected = match list.table_state.selected() {
+                        Some(selected) if selected < list.searches.saved.len() => selected,
+                        _ => continue,
+                    };
+
+                    let name = list.searches.saved[selected].name.clone();
+                    list.searches.remove(&name);
+                    list.new_maps.remove(selected);
+                    if selected >= list.searches.saved.len() {
+                        list.table_state
+                            .select(list.searches.saved.len().checked_sub(1));
+                    }
+
+                    if let Err(e) = list.searches.save() {
+                        notify_error("Failed to save the searches", e.as_ref());
+                    }
+                }
+                KeyCode::Char('n') => {
+                    let selected = match list.table_state.selected() {
+                        Some(selected) if selected < list.searches.saved.len() => selected,
+                        _ => continue,
+                    };
+
+                    let search = &mut list.searches.saved[selected];
+                    search.notify = !search.notify;
+
+                    if let Err(e) = list.searches.save() {
+                        notify_error("Failed to save the searches", e.as_ref());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn draw_saved_searches<B: Backend>(frame: &mut Frame<B>, list: &mut SavedSearchList) {
+    if draw_too_small(frame) {
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([Constraint::Length(1), Constraint::Min(1)].as_ref())
+        .split(frame.size());
+
+    frame.render_widget(
+        Paragraph::new(Text::from(Spans::from(Span::raw(
+            "Go Back(Esc) Run(Enter) Delete(d) Toggle notifications(n)",
+        )))),
+        chunks[0],
+    );
+
+    let header =
+        Row::new(["NAME", "QUERY", "FILTER", "SORT", "NOTIFY", "NEW"]).style(theme().header);
+
+    let rows: Vec<Row> = list
+        .searches
+        .saved
+        .iter()
+        .zip(&list.new_maps)
+        .map(|(search, new_maps)| {
+            let sort: Vec<String> = search.sort.iter().map(sort_label).collect();
+            let new_maps = match new_maps {
+                Some(0) | None => Cell::from(""),
+                Some(count) => Cell::from(count.to_string()).style(theme().success),
+            };
+
+            Row::new(vec![
+                Cell::from(search.name.clone()),
+                Cell::from(search.query.clone()),
+                Cell::from(search.filter.clone()),
+                Cell::from(sort.join(", ")),
+                Cell::from(if search.notify { "yes" } else { "no" }),
+                new_maps,
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows)
+        .header(header)
+        .widths(&[
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Percentage(8),
+            Constraint::Percentage(7),
+        ])
+        .highlight_style(theme().highlight)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Saved searches"),
+        );
+
+    frame.render_stateful_widget(table, chunks[1], &mut list.table_state);
+    draw_toasts(frame);
+}
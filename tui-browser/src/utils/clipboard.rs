@@ -0,0 +1,43 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, Write},
+    sync::Mutex,
+};
+
+use arboard::Clipboard;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Kept open since on X11 the copied text is only there for as long as its clipboard lives.
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+/// Copies the text to the clipboard. Over SSH that's the clipboard of the local terminal,
+/// through an OSC 52 escape sequence, otherwise the system clipboard, falling back to OSC 52
+/// when there's none like on a bare console.
+pub fn copy_to_clipboard(text: &str) -> Result<(), Box<dyn Error>> {
+    let remote = env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some();
+    if remote || copy_native(text).is_err() {
+        copy_osc52(text)?;
+    }
+    Ok(())
+}
+
+fn copy_native(text: &str) -> Result<(), arboard::Error> {
+    let mut clipboard = CLIPBOARD
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new()?);
+    }
+    clipboard
+        .as_mut()
+        .map_or(Ok(()), |clipboard| clipboard.set_text(text))
+}
+
+/// Asks the terminal to put the text on its clipboard, which most terminals support.
+fn copy_osc52(text: &str) -> Result<(), io::Error> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
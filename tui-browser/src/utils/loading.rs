@@ -25,7 +25,7 @@ impl<'a, B: Backend> Loading<'a, B> {
     }
 
     pub fn start(&mut self) {
-        let height = self.terminal.size().unwrap().height / 2 - 3;
+        let height = (self.terminal.size().unwrap().height / 2).saturating_sub(3);
         stdout().execute(MoveTo(0, height)).unwrap();
 
         self.terminal.clear().unwrap();
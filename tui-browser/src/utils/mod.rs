@@ -1,2 +1,7 @@
+pub mod clipboard;
 pub mod loading;
+pub mod mouse;
+pub mod open;
 pub mod preview_player;
+pub mod terminal;
+pub mod text;
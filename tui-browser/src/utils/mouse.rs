@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui::layout::Rect;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Two clicks on the same row within this time count as a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+pub fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.right() && row >= area.y && row < area.bottom()
+}
+
+/// Where a bordered table with a one line header was last drawn and which of its rows were
+/// visible. tui keeps the scroll offset of a `TableState` private, so it's tracked the same way
+/// here to find the row under the mouse.
+#[derive(Debug, Default)]
+pub struct TableRegion {
+    pub area: Rect,
+    offset: usize,
+    len: usize,
+}
+
+impl TableRegion {
+    /// Has to be called with the table's area and state every time the table is drawn.
+    pub fn update(&mut self, area: Rect, selected: Option<usize>, len: usize) {
+        self.area = area;
+        self.len = len;
+
+        // tui leaves the offset alone when there are no rows
+        if len == 0 {
+            return;
+        }
+
+        let visible = area.height.saturating_sub(3) as usize;
+        let selected = selected.unwrap_or(0).min(len - 1);
+        let offset = self.offset.min(len - 1);
+
+        self.offset = if selected >= offset + visible {
+            (selected + 1).saturating_sub(visible)
+        } else if selected < offset {
+            selected
+        } else {
+            offset
+        };
+    }
+
+    /// Index of the row at the given screen position, if there is one.
+    pub fn row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let rows = Rect {
+            x: self.area.x + 1,
+            y: self.area.y + 2,
+            width: self.area.width.saturating_sub(2),
+            height: self.area.height.saturating_sub(3),
+        };
+
+        if !contains(rows, column, row) {
+            return None;
+        }
+
+        let index = self.offset + (row - rows.y) as usize;
+        (index < self.len).then_some(index)
+    }
+}
+
+/// Tells double clicks apart from single ones.
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    last: Option<(Instant, u16)>,
+}
+
+impl ClickTracker {
+    /// Registers a click on `row`, returning whether it completes a double click.
+    pub fn click(&mut self, row: u16) -> bool {
+        let now = Instant::now();
+        let double = matches!(
+            self.last,
+            Some((time, last_row)) if last_row == row && now - time < DOUBLE_CLICK_TIME
+        );
+
+        self.last = if double { None } else { Some((now, row)) };
+        double
+    }
+}
+
+/// Finds the `Label(key)` entry of a key hint bar at `column`, counted from the bar's left edge,
+/// and returns the key press it stands for.
+pub fn hint_key(text: &str, column: u16) -> Option<KeyEvent> {
+    let mut entry_start = 0;
+    let mut key_start = None;
+    let mut x = 0;
+
+    for (i, c) in text.char_indices() {
+        let width = c.width().unwrap_or(0) as u16;
+
+        match c {
+            '(' => key_start = Some(i + 1),
+            ')' => {
+                if let Some(start) = key_start.take() {
+                    if (entry_start..=x).contains(&column) {
+                        return hint_key_code(&text[start..i])
+                            .map(|code| KeyEvent::new(code, KeyModifiers::NONE));
+                    }
+                }
+                entry_start = x + width;
+            }
+            '|' => entry_start = x + width,
+            _ => {}
+        }
+        x += width;
+    }
+    None
+}
+
+fn hint_key_code(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => return Some(KeyCode::Char(c)),
+        (None, _) => return None,
+        _ => {}
+    }
+
+    match key {
+        "Esc" => Some(KeyCode::Esc),
+        "Enter" => Some(KeyCode::Enter),
+        "Backspace" => Some(KeyCode::Backspace),
+        _ if key.starts_with("Tab") => Some(KeyCode::Tab),
+        _ => None,
+    }
+}
+
+/// Index of the tab at `column` in a `Tabs` widget drawn in `area` without a block.
+pub fn tab_at(titles: &[&str], area: Rect, column: u16) -> Option<usize> {
+    let mut x = area.x;
+
+    for (i, title) in titles.iter().enumerate() {
+        // every title is padded by a space on both sides and followed by a divider
+        let end = x + title.width() as u16 + 2;
+        if (x..end).contains(&column) {
+            return Some(i);
+        }
+        x = end + 1;
+    }
+    None
+}
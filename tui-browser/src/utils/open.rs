@@ -0,0 +1,29 @@
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+/// Opens the URL with the system's default handler, usually the web browser.
+pub fn open_url(url: &str) -> Result<(), io::Error> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    // anything the opener prints would end up on top of the TUI
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
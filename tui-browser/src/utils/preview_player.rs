@@ -3,13 +3,15 @@ use std::{
     io::{self, Cursor},
 };
 
+use common::api::fetch_bytes;
 use rodio::{Decoder, OutputStream, Sink};
 
 pub struct Preview {
     pub state: PreviewState,
-    pub sink: Sink,
-    _stream: OutputStream,
-    cursor: Cursor<Vec<u8>>,
+    /// Only open while the preview plays, so idle previews don't each hold an output stream.
+    output: Option<(OutputStream, Sink)>,
+    /// `None` if the preview couldn't be fetched.
+    audio: Option<Cursor<Vec<u8>>>,
     volume: f32,
 }
 
@@ -21,57 +23,75 @@ pub enum PreviewState {
 }
 
 impl Preview {
-    pub async fn new(url: &String) -> Result<Preview, Box<dyn Error>> {
-        let (_stream, stream_handle) = OutputStream::try_default().unwrap();
-        let sink = Sink::try_new(&stream_handle).unwrap();
-        let cursor = get_preview_audio(url).await;
-
-        match cursor {
-            Ok(cursor) => {
-                sink.set_volume(0.1);
-
-                Ok(Preview {
-                    state: PreviewState::Stopped,
-                    sink,
-                    _stream,
-                    cursor,
-                    volume: 0.1,
-                })
-            }
-            Err(_) => Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
-                "Failed to get preview audio",
-            ))),
+    /// Fetches the preview's audio, the audio device is only opened once it's played.
+    pub async fn new(url: &str) -> Result<Preview, Box<dyn Error>> {
+        let cursor = get_preview_audio(url).await?;
+
+        Ok(Preview {
+            audio: Some(cursor),
+            ..Preview::unavailable()
+        })
+    }
+
+    /// A preview without audio, for maps whose preview couldn't be fetched.
+    pub fn unavailable() -> Preview {
+        Preview {
+            state: PreviewState::Stopped,
+            output: None,
+            audio: None,
+            volume: 0.1,
         }
     }
 
-    pub fn play(&mut self) {
-        self.sink.append(Decoder::new(self.cursor.clone()).unwrap());
+    pub fn play(&mut self) -> Result<(), Box<dyn Error>> {
+        let audio = self
+            .audio
+            .clone()
+            .ok_or_else(|| io::Error::other("The preview couldn't be fetched"))?;
+        let source = Decoder::new(audio)?;
+
+        let (stream, stream_handle) = OutputStream::try_default()?;
+        let sink = Sink::try_new(&stream_handle)?;
+        sink.set_volume(self.volume);
+        sink.append(source);
+
+        self.output = Some((stream, sink));
         self.state = PreviewState::Playing;
+        Ok(())
     }
 
     pub fn resume(&mut self) {
-        self.sink.play();
+        if let Some((_, sink)) = &self.output {
+            sink.play();
+        }
         self.state = PreviewState::Playing;
     }
 
     pub fn pause(&mut self) {
-        self.sink.pause();
+        if let Some((_, sink)) = &self.output {
+            sink.pause();
+        }
         self.state = PreviewState::Paused;
     }
 
+    /// Stops playing and closes the audio device.
     pub fn stop(&mut self) {
-        self.sink.stop();
+        self.output = None;
         self.state = PreviewState::Stopped;
     }
 
+    /// Whether the preview played to the end.
+    pub fn finished(&self) -> bool {
+        self.output.as_ref().is_some_and(|(_, sink)| sink.empty())
+    }
+
     pub fn inc_vol(&mut self) {
         if self.volume == 1.0 {
             return;
         };
 
         self.volume += 0.02;
-        self.sink.set_volume(self.volume);
+        self.set_volume();
     }
 
     pub fn dec_vol(&mut self) {
@@ -79,19 +99,49 @@ impl Preview {
             return;
         }
         self.volume -= 0.02;
-        self.sink.set_volume(self.volume);
+        self.set_volume();
     }
-}
 
-async fn get_preview_audio(url: &String) -> Result<Cursor<Vec<u8>>, Box<dyn Error>> {
-    let response = match reqwest::get(url).await {
-        Ok(response) => response,
-        Err(_) => {
-            return Err(Box::new(io::Error::new(
-                io::ErrorKind::Other,
-                "Failed to get preview audio",
-            )));
+    fn set_volume(&self) {
+        if let Some((_, sink)) = &self.output {
+            sink.set_volume(self.volume);
         }
-    };
-    Ok(Cursor::new((response.bytes().await.unwrap()).to_vec()))
+    }
+}
+
+async fn get_preview_audio(url: &str) -> Result<Cursor<Vec<u8>>, Box<dyn Error>> {
+    Ok(Cursor::new(fetch_bytes(url).await?))
+}
+
+/// Plays the previews of several maps one after another.
+pub struct PreviewQueue {
+    sink: Sink,
+    _stream: OutputStream,
+}
+
+impl PreviewQueue {
+    /// Opens the audio device, the first preview added starts playing right away.
+    pub fn new() -> Result<PreviewQueue, Box<dyn Error>> {
+        let (_stream, stream_handle) = OutputStream::try_default()?;
+        let sink = Sink::try_new(&stream_handle)?;
+        sink.set_volume(0.1);
+
+        Ok(PreviewQueue { sink, _stream })
+    }
+
+    /// Fetches a preview and plays it after the ones already queued.
+    pub async fn add(&self, url: &str) -> Result<(), Box<dyn Error>> {
+        self.sink
+            .append(Decoder::new(get_preview_audio(url).await?)?);
+        Ok(())
+    }
+
+    /// Number of previews that haven't finished playing, including the current one.
+    pub fn remaining(&self) -> usize {
+        self.sink.len()
+    }
+
+    pub fn stop(&self) {
+        self.sink.stop();
+    }
 }
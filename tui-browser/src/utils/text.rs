@@ -0,0 +1,30 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Cuts `text` down to at most `width` terminal columns, ending it with `…` if anything was cut.
+pub fn ellipsize(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_owned();
+    }
+    if width == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        // leave a column for the ellipsis
+        if used + char_width >= width {
+            break;
+        }
+        result.push(c);
+        used += char_width;
+    }
+    result.push('…');
+    result
+}
+
+/// Right aligns `text` in `width` terminal columns.
+pub fn pad_left(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(text.width())), text)
+}